@@ -0,0 +1,34 @@
+use std::env;
+use std::error::Error;
+
+use a8mini_camera_rs::constants;
+use a8mini_camera_rs::emulator::Emulator;
+use tracing::info;
+use tracing_subscriber::EnvFilter;
+
+/// Runs an emulated A8 mini. Usage: `emulator [COMMAND_BIND_ADDR] [HTTP_BIND_ADDR]`, defaulting to all interfaces on
+/// the camera's command and HTTP ports.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+  tracing_subscriber::fmt()
+    .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+    .init();
+
+  let mut args = env::args().skip(1);
+  let command_addr = args
+    .next()
+    .unwrap_or_else(|| format!("0.0.0.0:{}", constants::CAMERA_COMMAND_PORT));
+  let http_addr = args
+    .next()
+    .unwrap_or_else(|| format!("0.0.0.0:{}", constants::CAMERA_HTTP_PORT));
+
+  let emulator = Emulator::bind(&command_addr).await?;
+  let media_server = emulator.media_server(&http_addr).await?;
+  info!("Emulated A8 mini listening on {}", emulator.local_addr()?);
+  info!("Emulated media server listening on {}", media_server.local_addr()?);
+
+  media_server.spawn();
+  emulator.run().await?;
+
+  Ok(())
+}
@@ -0,0 +1,32 @@
+use std::sync::Weak;
+
+use tokio::task::JoinHandle;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{debug, warn};
+
+use crate::Inner;
+
+/// Spawns the task pushing the host's UTC time to the camera of `inner` right away and then every
+/// `config::ClockSyncOptions::interval`. The task exits once `inner` is dropped.
+pub(crate) fn spawn(inner: Weak<Inner>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let Some(options) = inner.upgrade().map(|inner| inner.config.clock_sync) else {
+            return;
+        };
+
+        let mut ticks = interval(options.interval);
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticks.tick().await;
+            let Some(inner) = inner.upgrade() else {
+                return;
+            };
+
+            match inner.sync_clock().await {
+                Ok(time) => debug!(%time, "synced camera clock"),
+                Err(e) => warn!(error = %e, "failed to sync camera clock"),
+            }
+        }
+    })
+}
@@ -0,0 +1,452 @@
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{constants, control, A8MiniError};
+
+/// Connection settings for an `A8Mini`.
+///
+/// Settings are chained from the defaults, which match `A8Mini::connect`:
+///
+/// ```
+/// use a8mini_camera_rs::config::A8MiniConfig;
+/// use std::time::Duration;
+///
+/// let config = A8MiniConfig::default()
+///     .with_camera_ip("192.168.145.25")
+///     .with_local_command_port(0)
+///     .with_recv_timeout(Duration::from_millis(250));
+/// ```
+///
+/// They can also be loaded from a TOML or JSON file, where every field is optional and durations are given in
+/// milliseconds:
+///
+/// ```toml
+/// camera_ip = "192.168.145.25"
+/// local_command_port = 9000
+/// recv_timeout_ms = 250
+///
+/// [retry]
+/// max_attempts = 3
+///
+/// [http]
+/// timeout_ms = 10000
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct A8MiniConfig {
+    /// IP address of the camera.
+    pub camera_ip: String,
+    /// UDP port the camera accepts SDK commands on.
+    pub camera_command_port: u16,
+    /// TCP port of the camera's HTTP media server.
+    pub camera_http_port: u16,
+    /// Local address the sockets are bound to.
+    pub local_bind_addr: IpAddr,
+    /// Local port of the command socket. 0 picks an ephemeral port.
+    pub local_command_port: u16,
+    /// How long to wait for a command's ACK.
+    #[serde(rename = "recv_timeout_ms", with = "millis")]
+    pub recv_timeout: Duration,
+    /// How ACK-bearing commands are retried when they time out.
+    pub retry: RetryPolicy,
+    /// Options for HTTP queries.
+    pub http: HttpClientOptions,
+    /// Options for the heartbeat task tracking link health.
+    pub heartbeat: HeartbeatOptions,
+    /// How the link is re-established once it is lost.
+    pub reconnect: ReconnectPolicy,
+    /// Options for keeping the camera's clock in sync with the host's.
+    pub clock_sync: ClockSyncOptions,
+}
+
+impl Default for A8MiniConfig {
+    fn default() -> Self {
+        A8MiniConfig {
+            camera_ip: constants::CAMERA_IP.to_string(),
+            camera_command_port: constants::CAMERA_COMMAND_PORT.parse().expect("valid default port"),
+            camera_http_port: constants::CAMERA_HTTP_PORT.parse().expect("valid default port"),
+            local_bind_addr: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            local_command_port: 0,
+            recv_timeout: constants::RECV_TIMEOUT,
+            retry: RetryPolicy::default(),
+            http: HttpClientOptions::default(),
+            heartbeat: HeartbeatOptions::default(),
+            reconnect: ReconnectPolicy::default(),
+            clock_sync: ClockSyncOptions::default(),
+        }
+    }
+}
+
+impl A8MiniConfig {
+    pub fn with_camera_ip(mut self, camera_ip: &str) -> Self {
+        self.camera_ip = camera_ip.to_string();
+        self
+    }
+
+    pub fn with_camera_command_port(mut self, camera_command_port: u16) -> Self {
+        self.camera_command_port = camera_command_port;
+        self
+    }
+
+    pub fn with_camera_http_port(mut self, camera_http_port: u16) -> Self {
+        self.camera_http_port = camera_http_port;
+        self
+    }
+
+    pub fn with_local_bind_addr(mut self, local_bind_addr: IpAddr) -> Self {
+        self.local_bind_addr = local_bind_addr;
+        self
+    }
+
+    pub fn with_local_command_port(mut self, local_command_port: u16) -> Self {
+        self.local_command_port = local_command_port;
+        self
+    }
+
+    pub fn with_recv_timeout(mut self, recv_timeout: Duration) -> Self {
+        self.recv_timeout = recv_timeout;
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_http(mut self, http: HttpClientOptions) -> Self {
+        self.http = http;
+        self
+    }
+
+    pub fn with_heartbeat(mut self, heartbeat: HeartbeatOptions) -> Self {
+        self.heartbeat = heartbeat;
+        self
+    }
+
+    pub fn with_reconnect(mut self, reconnect: ReconnectPolicy) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+
+    pub fn with_clock_sync(mut self, clock_sync: ClockSyncOptions) -> Self {
+        self.clock_sync = clock_sync;
+        self
+    }
+
+    /// Address of the camera's command socket.
+    pub fn camera_command_addr(&self) -> String {
+        format!("{}:{}", self.camera_ip, self.camera_command_port)
+    }
+
+    /// Base URL `HTTPQuery`s are rendered against.
+    pub fn http_base_url(&self) -> String {
+        match &self.http.base_url {
+            Some(base_url) => base_url.trim_end_matches('/').to_string(),
+            None => control::http_base_url(&self.camera_ip, &self.camera_http_port.to_string()),
+        }
+    }
+
+    /// Rejects settings the background tasks cannot run with, whether or not they start on connect.
+    pub fn validate(&self) -> Result<(), A8MiniError> {
+        let heartbeat = &self.heartbeat;
+        if heartbeat.interval.is_zero() {
+            return Err(A8MiniError::InvalidConfig("heartbeat interval must not be zero".to_string()));
+        }
+        if heartbeat.degraded_after > heartbeat.lost_after {
+            return Err(A8MiniError::InvalidConfig(format!(
+                "heartbeat degraded_after ({:?}) exceeds lost_after ({:?})",
+                heartbeat.degraded_after, heartbeat.lost_after
+            )));
+        }
+        if self.clock_sync.interval.is_zero() {
+            return Err(A8MiniError::InvalidConfig("clock sync interval must not be zero".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Parses and validates a configuration from TOML.
+    pub fn from_toml_str(toml: &str) -> Result<Self, A8MiniError> {
+        let config: Self = toml::from_str(toml).map_err(|e| A8MiniError::InvalidConfig(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Parses and validates a configuration from JSON.
+    pub fn from_json_str(json: &str) -> Result<Self, A8MiniError> {
+        let config: Self = serde_json::from_str(json).map_err(|e| A8MiniError::InvalidConfig(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Loads a configuration from a `.toml` or `.json` file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, A8MiniError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::from_toml_str(&contents),
+            Some("json") => Self::from_json_str(&contents),
+            _ => Err(A8MiniError::InvalidConfig(format!(
+                "{} is neither a .toml nor a .json file",
+                path.display()
+            ))),
+        }
+    }
+}
+
+/// How ACK-bearing commands are retried when they time out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first. 1 disables retries.
+    pub max_attempts: u32,
+    /// Delay between attempts.
+    #[serde(rename = "delay_ms", with = "millis")]
+    pub delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            delay: Duration::ZERO,
+        }
+    }
+}
+
+/// Options for the HTTP client used by `HTTPQuery`s.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpClientOptions {
+    /// Overrides the base URL derived from `camera_ip` and `camera_http_port`.
+    pub base_url: Option<String>,
+    /// Timeout for a whole request, including downloading the body.
+    #[serde(rename = "timeout_ms", with = "millis")]
+    pub timeout: Duration,
+    /// Timeout for establishing the connection.
+    #[serde(rename = "connect_timeout_ms", with = "millis")]
+    pub connect_timeout: Duration,
+}
+
+impl Default for HttpClientOptions {
+    fn default() -> Self {
+        HttpClientOptions {
+            base_url: None,
+            timeout: Duration::from_secs(30),
+            connect_timeout: Duration::from_secs(3),
+        }
+    }
+}
+
+/// Options for the heartbeat task tracking link health.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HeartbeatOptions {
+    /// Whether the heartbeat task is started on connect.
+    pub enabled: bool,
+    /// Delay between heartbeats.
+    #[serde(rename = "interval_ms", with = "millis")]
+    pub interval: Duration,
+    /// Silence after which the link is reported `Degraded`.
+    #[serde(rename = "degraded_after_ms", with = "millis")]
+    pub degraded_after: Duration,
+    /// Silence after which the link is reported `Lost`.
+    #[serde(rename = "lost_after_ms", with = "millis")]
+    pub lost_after: Duration,
+}
+
+impl Default for HeartbeatOptions {
+    fn default() -> Self {
+        HeartbeatOptions {
+            enabled: false,
+            interval: Duration::from_secs(1),
+            degraded_after: Duration::from_secs(3),
+            lost_after: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Options for the task pushing the host's UTC time to the camera, so media timestamps match the host's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClockSyncOptions {
+    /// Whether the clock sync task is started on connect, which is the default. It syncs right away, then every
+    /// `interval`.
+    pub enabled: bool,
+    /// Delay between syncs.
+    #[serde(rename = "interval_ms", with = "millis")]
+    pub interval: Duration,
+}
+
+impl Default for ClockSyncOptions {
+    fn default() -> Self {
+        ClockSyncOptions {
+            enabled: true,
+            interval: Duration::from_secs(60),
+        }
+    }
+}
+
+/// How the link is re-established once it is lost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReconnectPolicy {
+    /// Whether the reconnect supervisor is started on connect. It reacts to the heartbeat task reporting the link
+    /// `Lost` and to `max_consecutive_failures`.
+    pub enabled: bool,
+    /// Number of requests in a row that time out or fail to send after which the link is considered lost. 0
+    /// disables this check.
+    pub max_consecutive_failures: u32,
+    /// Delay before the first reconnect attempt, doubled after every failed attempt.
+    #[serde(rename = "initial_backoff_ms", with = "millis")]
+    pub initial_backoff: Duration,
+    /// Upper bound of the exponential backoff, before jitter.
+    #[serde(rename = "max_backoff_ms", with = "millis")]
+    pub max_backoff: Duration,
+    /// Upper bound of the random delay added to every backoff.
+    #[serde(rename = "max_jitter_ms", with = "millis")]
+    pub max_jitter: Duration,
+    /// Number of attempts before giving up. 0 retries forever.
+    pub max_attempts: u32,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        ReconnectPolicy {
+            enabled: false,
+            max_consecutive_failures: 3,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(30),
+            max_jitter: Duration::from_millis(250),
+            max_attempts: 0,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Delay before the `attempt`th reconnect attempt, counting from 1.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let backoff = 2u32
+            .checked_pow(attempt.saturating_sub(1))
+            .and_then(|factor| self.initial_backoff.checked_mul(factor))
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff));
+        let jitter = Duration::from_nanos(fastrand::u64(..=self.max_jitter.as_nanos() as u64));
+
+        backoff + jitter
+    }
+}
+
+/// (De)serializes a `Duration` as whole milliseconds.
+mod millis {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_millis() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Ok(Duration::from_millis(u64::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_from_toml_and_json() {
+        let from_toml = A8MiniConfig::from_toml_str(
+            r#"
+            camera_ip = "192.168.145.25"
+            local_bind_addr = "192.168.145.10"
+            local_command_port = 9000
+            recv_timeout_ms = 250
+
+            [retry]
+            max_attempts = 3
+            delay_ms = 50
+
+            [http]
+            base_url = "http://192.168.145.25:82/"
+            "#,
+        )
+        .unwrap();
+
+        let expected = A8MiniConfig::default()
+            .with_camera_ip("192.168.145.25")
+            .with_local_bind_addr("192.168.145.10".parse().unwrap())
+            .with_local_command_port(9000)
+            .with_recv_timeout(Duration::from_millis(250))
+            .with_retry(RetryPolicy {
+                max_attempts: 3,
+                delay: Duration::from_millis(50),
+            })
+            .with_http(HttpClientOptions {
+                base_url: Some("http://192.168.145.25:82/".to_string()),
+                ..HttpClientOptions::default()
+            });
+        assert_eq!(from_toml, expected);
+        assert_eq!(from_toml.http_base_url(), "http://192.168.145.25:82");
+        assert_eq!(from_toml.camera_command_addr(), "192.168.145.25:37260");
+
+        let json = serde_json::to_string(&expected).unwrap();
+        assert_eq!(A8MiniConfig::from_json_str(&json).unwrap(), expected);
+        assert_eq!(A8MiniConfig::from_json_str("{}").unwrap(), A8MiniConfig::default());
+    }
+
+    #[test]
+    fn test_reconnect_backoff() {
+        let policy = ReconnectPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+            max_jitter: Duration::ZERO,
+            ..ReconnectPolicy::default()
+        };
+        let backoffs: Vec<u128> = (1..=6).map(|attempt| policy.backoff(attempt).as_millis()).collect();
+        assert_eq!(backoffs, [100, 200, 400, 800, 1000, 1000]);
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(1));
+
+        let jittered = ReconnectPolicy {
+            max_jitter: Duration::from_millis(50),
+            ..policy
+        };
+        for _ in 0..100 {
+            let backoff = jittered.backoff(2);
+            assert!(backoff >= Duration::from_millis(200) && backoff <= Duration::from_millis(250));
+        }
+    }
+
+    #[test]
+    fn test_config_rejects_unknown_fields() {
+        let result = A8MiniConfig::from_toml_str("camera_addr = \"10.0.0.1\"");
+        assert!(matches!(result, Err(A8MiniError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_config_rejects_unusable_heartbeat() {
+        let result = A8MiniConfig::from_toml_str("[heartbeat]\ninterval_ms = 0");
+        assert!(matches!(result, Err(A8MiniError::InvalidConfig(_))));
+
+        let result = A8MiniConfig::from_json_str(r#"{"heartbeat": {"degraded_after_ms": 5000, "lost_after_ms": 2000}}"#);
+        assert!(matches!(result, Err(A8MiniError::InvalidConfig(_))));
+
+        assert!(A8MiniConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_config_rejects_zero_clock_sync_interval() {
+        let result = A8MiniConfig::from_toml_str("[clock_sync]\ninterval_ms = 0");
+        assert!(matches!(result, Err(A8MiniError::InvalidConfig(_))));
+
+        let clock_sync = ClockSyncOptions {
+            interval: Duration::ZERO,
+            ..ClockSyncOptions::default()
+        };
+        assert!(A8MiniConfig::default().with_clock_sync(clock_sync).validate().is_err());
+    }
+}
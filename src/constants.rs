@@ -1,12 +1,15 @@
 use std::time::Duration;
 pub const RECV_BUFF_SIZE: usize = 64;
 pub const RECV_TIMEOUT: Duration = Duration::from_millis(500);
+pub const STATE_CONFIRM_TIMEOUT: Duration = Duration::from_secs(2);
+pub const STATE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+pub const PHOTO_CAPTURE_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub const CAMERA_IP: &str = "192.168.144.25";
 pub const CAMERA_COMMAND_PORT: &str = "37260";
 pub const CAMERA_HTTP_PORT: &str = "82";
 
-pub const API_BASE_URL: &str = "//cgi-bin/media.cgi";
+pub const API_BASE_URL: &str = "/cgi-bin/media.cgi";
 
 // ### SDK PROTOCOL FORMAT
 // +-----------+-------+---------+---------------------------------------------------+
@@ -37,6 +40,11 @@ pub const API_BASE_URL: &str = "//cgi-bin/media.cgi";
 // +---------+----+---------+---------+----+------- ... --+---------+
 // |   STX   |CTRL| DATALEN |   SEQ   | CMD|  DATA  ...   |  CRC16  |
 // +---------+----+---------+---------+----+------- ... --+---------+
+pub const STX: [u8; 2] = [0x55, 0x66];
+pub const CTRL_DEFAULT: u8 = 0x01;
+pub const FRAME_HEADER_LEN: usize = 8;
+pub const FRAME_CRC_LEN: usize = 2;
+
 pub const NUM_COMMANDS: usize = 31; // update this if more commands are added
 pub const HARDCODED_COMMANDS: [&[u8]; NUM_COMMANDS] = [
     &[
@@ -136,12 +144,10 @@ pub const HARDCODED_COMMANDS: [&[u8]; NUM_COMMANDS] = [
     &[
         0x55, 0x66, 0x01, 0x02, 0x00, 0x00, 0x00, 0x80, 0x00, 0x01, 0x1a, 0x8a,
     ], // Reboot Gimbal
-    &[
-        0x55, 0x66, 0x01, 0x09, 0x00, 0x00, 0x00, 0x21, 0x00, 0x02, 0x00, 0x0f, 0x70, 0x08, 0x98, 0x3a, 0x00, 0x70, 0xbe,
-    ], // Resolution 4k
     &[
         0x55, 0x66, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x59, 0x8B,
     ], // Heartbeat
+    &[0x55, 0x66, 0x01, 0x00, 0x00, 0x00, 0x00, 0x18, 0x7c, 0x47], // Acquire the Current Zoom Value
 ];
 
 pub const CRC16_TAB: [u16; 256] = [
@@ -1,15 +1,77 @@
-use crate::{checksum, constants};
+use crate::constants;
+use crate::frame::{Frame, FrameError};
+use crate::A8MiniError;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 
 /// Trait for camera commands
-pub trait Command {
-    fn to_bytes(&self) -> Vec<u8>;
+pub trait Command: fmt::Debug {
+    fn to_frame(&self) -> Frame;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_frame().to_bytes()
+    }
+}
+
+/// Trait for typed ACK replies decoded from a `Frame`
+pub trait Response: Sized {
+    /// CMD_ID of the frames carrying this reply.
+    const CMD_ID: u8;
+
+    /// Decodes the reply from the DATA field of a frame.
+    fn from_data(data: &[u8]) -> Result<Self, FrameError>;
+
+    /// Decodes the reply from a frame, checking its CMD_ID first.
+    fn from_frame(frame: &Frame) -> Result<Self, FrameError> {
+        if frame.cmd_id != Self::CMD_ID {
+            return Err(FrameError::UnexpectedCmdId {
+                expected: Self::CMD_ID,
+                actual: frame.cmd_id,
+            });
+        }
+
+        Self::from_data(&frame.data)
+    }
+}
+
+/// Checks that a reply's DATA field is exactly `expected` bytes long.
+fn expect_data_len(cmd_id: u8, data: &[u8], expected: usize) -> Result<(), FrameError> {
+    if data.len() != expected {
+        return Err(FrameError::MalformedPayload {
+            cmd_id,
+            expected,
+            actual: data.len(),
+        });
+    }
+
+    Ok(())
 }
 
 /// Trait for HTTP API queries
-pub trait HTTPQuery {
-    fn to_string(&self) -> String;
+pub trait HTTPQuery: fmt::Debug {
+    /// Path and query string of the request, relative to the camera's HTTP base URL.
+    fn path(&self) -> String;
+
+    /// Renders the full URL against `base_url`, e.g. `http://192.168.144.25:82`.
+    fn to_url(&self, base_url: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), self.path())
+    }
+
+    /// Renders the full URL against the default camera address.
+    fn to_string(&self) -> String {
+        self.to_url(&default_http_base_url())
+    }
+}
+
+/// HTTP base URL of a camera at the default address.
+pub fn default_http_base_url() -> String {
+    http_base_url(constants::CAMERA_IP, constants::CAMERA_HTTP_PORT)
+}
+
+/// HTTP base URL of a camera at `camera_ip` serving HTTP on `camera_http_port`.
+pub fn http_base_url(camera_ip: &str, camera_http_port: &str) -> String {
+    format!("http://{}:{}", camera_ip, camera_http_port)
 }
 
 /// Enums for hardcoded simple commands.
@@ -21,14 +83,14 @@ pub enum A8MiniSimpleCommand {
     RotateRight = 3,  // handled ACK (sta)
     RotateLeft = 4,   // handled ACK (sta)
     StopRotation = 5, // handled ACK (sta)
-    ZoomIn = 6,       // handled ACK (sta)
-    ZoomOut = 7,      // handled ACK (sta)
-    ZoomMax = 8,
+    ZoomIn = 6,       // handled ACK (zoom multiple)
+    ZoomOut = 7,      // handled ACK (zoom multiple)
+    ZoomMax = 8,      // sets exactly 4.5x, see A8MiniComplexCommand::SetAbsoluteZoom
     MaxZoomInformation = 9,
     FocusIn = 10,
     FocusOut = 11,
-    TakePicture = 12, // no ACK
-    RecordVideo = 13, // no ACK
+    TakePicture = 12, // no ACK, reported as CameraEvent
+    RecordVideo = 13, // no ACK, reported as CameraEvent
     Rotate100100 = 14,
     CameraInformation = 15,
     AutoFocus = 16,   // handled ACK (sta)
@@ -44,82 +106,103 @@ pub enum A8MiniSimpleCommand {
     LaserRangefinderInformation = 26,
     RebootCamera = 27,
     RebootGimbal = 28,
-    Resolution4k = 29,
-    Heartbeat = 30,
+    Heartbeat = 29,
+    CurrentZoomInformation = 30,
 }
 
 impl Command for A8MiniSimpleCommand {
-    fn to_bytes(&self) -> Vec<u8> {
-        constants::HARDCODED_COMMANDS[*self as usize].to_vec()
+    fn to_frame(&self) -> Frame {
+        Frame::from_bytes(constants::HARDCODED_COMMANDS[*self as usize])
+            .expect("hardcoded commands are valid frames")
     }
 }
 
 /// Enums for commands that require continuous values for data field.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum A8MiniComplexCommand {
     SetYawPitchSpeed(i8, i8),
     SetYawPitchAngle(i16, i16),
-    SetTimeUTC(u64),
-    GetCodecSpecs(u8), // TODO: WIP
-    SetCodecSpecs(u8, u8, u16, u16, u16, u8), // TODO: WIP
+    /// Zoom multiple, rounded to a tenth. Values outside 1.0-30.0 are clamped into that range when encoded;
+    /// `A8Mini::zoom_to` rejects them against the camera's actual maximum instead.
+    SetAbsoluteZoom(f32), // handled ACK (sta)
+    AutoFocusAt { x: u16, y: u16 }, // handled ACK (sta), in video pixels
+    ManualFocus(i8), // handled ACK (sta), 1 focuses far, -1 near, 0 stops
+    SetTimeUTC(u64), // handled ACK (sta), microseconds since the Unix epoch
+    GetCodecSpecs(StreamType), // handled ACK (CodecSpec)
+    SetCodecSpecs(CodecSpec), // handled ACK (stream type, sta), the frame rate is not sent
 }
 
 impl Command for A8MiniComplexCommand {
-    fn to_bytes(&self) -> Vec<u8> {
+    fn to_frame(&self) -> Frame {
         match *self {
             A8MiniComplexCommand::SetYawPitchSpeed(v_yaw, v_pitch) => {
-                let mut byte_arr: Vec<u8> = vec![0x55, 0x66, 0x01, 0x02, 0x00, 0x00, 0x00, 0x07];
-
-                byte_arr.push(v_yaw.clamp(-100, 100) as u8);
-                byte_arr.push(v_pitch.clamp(-100, 100) as u8);
-
-                byte_arr.extend_from_slice(&checksum::crc16_calc(&byte_arr, 0));
+                let data = vec![
+                    v_yaw.clamp(-100, 100) as u8,
+                    v_pitch.clamp(-100, 100) as u8,
+                ];
 
-                byte_arr
+                Frame::new(0x07, data)
             },
             A8MiniComplexCommand::SetYawPitchAngle(theta_yaw, theta_pitch) => {
-                let mut byte_arr: Vec<u8> = vec![0x55, 0x66, 0x01, 0x04, 0x00, 0x00, 0x00, 0x0e];
+                let mut data: Vec<u8> = Vec::with_capacity(4);
 
-                byte_arr.extend_from_slice(&theta_yaw.clamp(-1350, 1350).to_le_bytes());
-                byte_arr.extend_from_slice(&theta_pitch.clamp(-900, 250).to_le_bytes());
+                data.extend_from_slice(&theta_yaw.clamp(-1350, 1350).to_le_bytes());
+                data.extend_from_slice(&theta_pitch.clamp(-900, 250).to_le_bytes());
 
-                byte_arr.extend_from_slice(&checksum::crc16_calc(&byte_arr, 0));
+                Frame::new(0x0e, data)
+            },
+            A8MiniComplexCommand::SetAbsoluteZoom(zoom) => {
+                let [zoom_int, zoom_float] = zoom_to_bytes(zoom.clamp(1.0, 30.0));
 
-                byte_arr
+                Frame::new(0x0f, vec![zoom_int, zoom_float])
             },
-            A8MiniComplexCommand::SetTimeUTC(timestamp) => {
-                let mut byte_arr: Vec<u8> = vec![0x55, 0x66, 0x01, 0x04, 0x00, 0x00, 0x00, 0x30];
+            A8MiniComplexCommand::AutoFocusAt { x, y } => {
+                let mut data: Vec<u8> = Vec::with_capacity(5);
 
-                byte_arr.extend_from_slice(&timestamp.to_be_bytes());
+                data.push(1);
+                data.extend_from_slice(&x.to_le_bytes());
+                data.extend_from_slice(&y.to_le_bytes());
 
-                byte_arr
+                Frame::new(0x04, data)
+            },
+            A8MiniComplexCommand::ManualFocus(direction) => {
+                Frame::new(0x06, vec![direction.signum() as u8])
+            },
+            A8MiniComplexCommand::SetTimeUTC(timestamp) => {
+                Frame::new(0x30, timestamp.to_le_bytes().to_vec())
             },
             A8MiniComplexCommand::GetCodecSpecs(stream_type) => {
-                let mut byte_arr: Vec<u8> = vec![0x55, 0x66, 0x01, 0x04, 0x00, 0x00, 0x00, 0x20];
-
-                byte_arr.extend_from_slice(&stream_type.clamp(0, 2).to_be_bytes());
-
-                byte_arr
+                Frame::new(0x20, vec![stream_type as u8])
             },
-            A8MiniComplexCommand::SetCodecSpecs(stream_type, video_enc_type, resolution_l, resolution_h, video_bitrate, _) => {
-                let mut byte_arr: Vec<u8> = vec![0x55, 0x66, 0x01, 0x04, 0x00, 0x00, 0x00, 0x21];
+            A8MiniComplexCommand::SetCodecSpecs(spec) => {
+                let mut data: Vec<u8> = Vec::with_capacity(9);
 
-                byte_arr.extend_from_slice(&stream_type.clamp(0, 2).to_be_bytes());
-                byte_arr.extend_from_slice(&video_enc_type.clamp(1, 2).to_be_bytes());
+                data.push(spec.stream_type as u8);
+                data.push(spec.encoding as u8);
+                data.extend_from_slice(&spec.width.to_le_bytes());
+                data.extend_from_slice(&spec.height.to_le_bytes());
+                data.extend_from_slice(&spec.bitrate_kbps.to_le_bytes());
 
-                // TODO: make sure resolution_l and resolution_h are clamped to only 1920/1280 and 1080/720 respectively
-                byte_arr.extend_from_slice(&resolution_l.to_be_bytes());
-                byte_arr.extend_from_slice(&resolution_h.to_be_bytes());
-                
-                // TODO: make sure video bitrate is reasonable
-                byte_arr.extend_from_slice(&video_bitrate.to_be_bytes());
+                // Reserved
+                data.push(0);
 
-                byte_arr
+                Frame::new(0x21, data)
             },
         }
     }
 }
 
+/// Splits a zoom multiple into its integer part and tenths, as used on the wire.
+fn zoom_to_bytes(zoom: f32) -> [u8; 2] {
+    let tenths = (zoom * 10.0).round() as u16;
+    [(tenths / 10) as u8, (tenths % 10) as u8]
+}
+
+/// Combines a zoom multiple's integer part and tenths, as used on the wire.
+fn zoom_from_bytes(zoom_int: u8, zoom_float: u8) -> f32 {
+    (zoom_int as u16 * 10 + zoom_float as u16) as f32 / 10.0
+}
+
 /// Enums for simple HTTP queries
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum A8MiniSimpleHTTPQuery {
@@ -130,12 +213,12 @@ pub enum A8MiniSimpleHTTPQuery {
 }
 
 impl HTTPQuery for A8MiniSimpleHTTPQuery {
-    fn to_string(&self) -> String {
+    fn path(&self) -> String {
         match *self {
-            A8MiniSimpleHTTPQuery::GetDirectoriesPhotos => "http://192.168.144.25:82/cgi-bin/media.cgi/api/v1/getdirectories?media_type=0".to_string(),
-            A8MiniSimpleHTTPQuery::GetDirectoriesVideos => "http://192.168.144.25:82/cgi-bin/media.cgi/api/v1/getdirectories?media_type=1".to_string(),
-            A8MiniSimpleHTTPQuery::GetMediaCountPhotos => "http://192.168.144.25:82/cgi-bin/media.cgi/api/v1/getmediacount?media_type=0&path=101SIYI_IMG".to_string(),
-            A8MiniSimpleHTTPQuery::GetMediaCountVideos => "http://192.168.144.25:82/cgi-bin/media.cgi/api/v1/getmediacount?media_type=1&path=100SIYI_VID".to_string(),
+            A8MiniSimpleHTTPQuery::GetDirectoriesPhotos => format!("{}/api/v1/getdirectories?media_type=0", constants::API_BASE_URL),
+            A8MiniSimpleHTTPQuery::GetDirectoriesVideos => format!("{}/api/v1/getdirectories?media_type=1", constants::API_BASE_URL),
+            A8MiniSimpleHTTPQuery::GetMediaCountPhotos => format!("{}/api/v1/getmediacount?media_type=0&path=101SIYI_IMG", constants::API_BASE_URL),
+            A8MiniSimpleHTTPQuery::GetMediaCountVideos => format!("{}/api/v1/getmediacount?media_type=1&path=100SIYI_VID", constants::API_BASE_URL),
         }
     }
 }
@@ -147,17 +230,21 @@ pub enum A8MiniComplexHTTPQuery {
     GetVideo(u32),
 }
 
+impl A8MiniComplexHTTPQuery {
+    /// Camera-side file name of the photo or video.
+    pub fn file_name(&self) -> String {
+        match *self {
+            A8MiniComplexHTTPQuery::GetPhoto(photo_ind) => format!("IMG_{:0>4}.jpg", photo_ind),
+            A8MiniComplexHTTPQuery::GetVideo(video_ind) => format!("REC_{:0>4}.mp4", video_ind),
+        }
+    }
+}
+
 impl HTTPQuery for A8MiniComplexHTTPQuery {
-    fn to_string(&self) -> String {
+    fn path(&self) -> String {
         match *self {
-            A8MiniComplexHTTPQuery::GetPhoto(photo_ind) => format!(
-                "http://192.168.144.25:82/photo/101SIYI_IMG/IMG_{:0>4}.jpg",
-                photo_ind
-            ),
-            A8MiniComplexHTTPQuery::GetVideo(video_ind) => format!(
-                "http://192.168.144.25:82/photo/100SIYI_VID/REC_{:0>4}.mp4",
-                video_ind
-            ),
+            A8MiniComplexHTTPQuery::GetPhoto(_) => format!("/photo/101SIYI_IMG/{}", self.file_name()),
+            A8MiniComplexHTTPQuery::GetVideo(_) => format!("/photo/100SIYI_VID/{}", self.file_name()),
         }
     }
 }
@@ -187,7 +274,7 @@ pub struct HTTPResponseData {
     pub list: Option<String>,
 }
 
-/// Camera attitude information
+/// Camera attitude information. Angles are in tenths of a degree, velocities in tenths of a degree per second.
 #[derive(Debug, PartialEq, Eq, Deserialize)]
 pub struct A8MiniAtittude {
     pub theta_yaw: i16,
@@ -198,6 +285,661 @@ pub struct A8MiniAtittude {
     pub v_roll: i16,
 }
 
+impl A8MiniAtittude {
+    /// Yaw angle in degrees.
+    pub fn yaw_deg(&self) -> f32 {
+        self.theta_yaw as f32 / 10.0
+    }
+
+    /// Pitch angle in degrees.
+    pub fn pitch_deg(&self) -> f32 {
+        self.theta_pitch as f32 / 10.0
+    }
+
+    /// Roll angle in degrees.
+    pub fn roll_deg(&self) -> f32 {
+        self.theta_roll as f32 / 10.0
+    }
+
+    /// Yaw velocity in degrees per second.
+    pub fn v_yaw_deg(&self) -> f32 {
+        self.v_yaw as f32 / 10.0
+    }
+
+    /// Pitch velocity in degrees per second.
+    pub fn v_pitch_deg(&self) -> f32 {
+        self.v_pitch as f32 / 10.0
+    }
+
+    /// Roll velocity in degrees per second.
+    pub fn v_roll_deg(&self) -> f32 {
+        self.v_roll as f32 / 10.0
+    }
+}
+
+impl Response for A8MiniAtittude {
+    const CMD_ID: u8 = 0x0d;
+
+    fn from_data(data: &[u8]) -> Result<Self, FrameError> {
+        expect_data_len(Self::CMD_ID, data, 12)?;
+
+        let field = |i: usize| i16::from_le_bytes([data[2 * i], data[2 * i + 1]]);
+        Ok(A8MiniAtittude {
+            theta_yaw: field(0),
+            theta_pitch: field(1),
+            theta_roll: field(2),
+            v_yaw: field(3),
+            v_pitch: field(4),
+            v_roll: field(5),
+        })
+    }
+}
+
+/// Recording state reported in a `GimbalConfig`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RecordingState {
+    NotRecording,
+    Recording,
+    /// No TF card is inserted.
+    NoTfCard,
+    /// The TF card holds corrupted recordings.
+    TfCardDataLoss,
+    /// A state this crate does not know.
+    Unknown(u8),
+}
+
+/// Gimbal motion mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GimbalMode {
+    /// The gimbal holds its heading regardless of the aircraft's.
+    Lock,
+    /// The gimbal follows the aircraft's heading.
+    Follow,
+    /// The gimbal follows the aircraft on all axes.
+    Fpv,
+}
+
+impl From<GimbalMode> for A8MiniSimpleCommand {
+    /// The hardcoded command switching to `mode`.
+    fn from(mode: GimbalMode) -> Self {
+        match mode {
+            GimbalMode::Lock => A8MiniSimpleCommand::SetLockMode,
+            GimbalMode::Follow => A8MiniSimpleCommand::SetFollowMode,
+            GimbalMode::Fpv => A8MiniSimpleCommand::SetFPVMode,
+        }
+    }
+}
+
+/// Mounting direction of the gimbal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MountingDirection {
+    Normal,
+    UpsideDown,
+}
+
+/// Video output port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VideoOutput {
+    Hdmi,
+    Cvbs,
+    /// Video output is turned off.
+    Off,
+    /// An output this crate does not know.
+    Unknown(u8),
+}
+
+/// Camera status and gimbal configuration, replied to `A8MiniSimpleCommand::CameraInformation`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GimbalConfig {
+    /// `None` if the camera reports an unknown HDR state.
+    pub hdr_enabled: Option<bool>,
+    pub recording: RecordingState,
+    /// `None` if the camera reports an unknown motion mode.
+    pub motion_mode: Option<GimbalMode>,
+    /// `None` if the camera does not report it.
+    pub mounting_direction: Option<MountingDirection>,
+    pub video_output: VideoOutput,
+}
+
+impl GimbalConfig {
+    /// Whether the camera is currently recording.
+    pub fn is_recording(&self) -> bool {
+        self.recording == RecordingState::Recording
+    }
+}
+
+impl Response for GimbalConfig {
+    const CMD_ID: u8 = 0x0a;
+
+    fn from_data(data: &[u8]) -> Result<Self, FrameError> {
+        expect_data_len(Self::CMD_ID, data, 7)?;
+
+        // Bytes 0 and 2 are reserved. Unknown values are kept rather than failing the whole status, since newer
+        // firmware may report states this crate does not know.
+        Ok(GimbalConfig {
+            hdr_enabled: match data[1] {
+                0 => Some(false),
+                1 => Some(true),
+                _ => None,
+            },
+            recording: match data[3] {
+                0 => RecordingState::NotRecording,
+                1 => RecordingState::Recording,
+                2 => RecordingState::NoTfCard,
+                3 => RecordingState::TfCardDataLoss,
+                value => RecordingState::Unknown(value),
+            },
+            motion_mode: match data[4] {
+                0 => Some(GimbalMode::Lock),
+                1 => Some(GimbalMode::Follow),
+                2 => Some(GimbalMode::Fpv),
+                _ => None,
+            },
+            mounting_direction: match data[5] {
+                1 => Some(MountingDirection::Normal),
+                2 => Some(MountingDirection::UpsideDown),
+                _ => None,
+            },
+            video_output: match data[6] {
+                0 => VideoOutput::Hdmi,
+                1 => VideoOutput::Cvbs,
+                2 => VideoOutput::Off,
+                value => VideoOutput::Unknown(value),
+            },
+        })
+    }
+}
+
+/// Reply to `A8MiniComplexCommand::SetAbsoluteZoom`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct A8MiniAbsoluteZoomAck {
+    /// Whether the camera accepted the zoom multiple.
+    pub accepted: bool,
+}
+
+impl Response for A8MiniAbsoluteZoomAck {
+    const CMD_ID: u8 = 0x0f;
+
+    fn from_data(data: &[u8]) -> Result<Self, FrameError> {
+        expect_data_len(Self::CMD_ID, data, 1)?;
+        Ok(A8MiniAbsoluteZoomAck { accepted: data[0] == 1 })
+    }
+}
+
+/// Reply to `A8MiniSimpleCommand::AutoFocus` and `A8MiniComplexCommand::AutoFocusAt`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct A8MiniAutoFocusAck {
+    /// Whether the camera started focusing.
+    pub accepted: bool,
+}
+
+impl Response for A8MiniAutoFocusAck {
+    const CMD_ID: u8 = 0x04;
+
+    fn from_data(data: &[u8]) -> Result<Self, FrameError> {
+        expect_data_len(Self::CMD_ID, data, 1)?;
+        Ok(A8MiniAutoFocusAck { accepted: data[0] == 1 })
+    }
+}
+
+/// Video stream of the camera.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StreamType {
+    /// Stream written to the TF card.
+    Recording = 0,
+    /// Main live stream.
+    Main = 1,
+    /// Secondary live stream.
+    Sub = 2,
+}
+
+/// Video encoding of a stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VideoEncoding {
+    H264 = 1,
+    H265 = 2,
+}
+
+/// Encoding settings of a video stream.
+///
+/// `CodecSpec::new` only admits combinations the A8 mini supports; specs decoded from the camera are taken as
+/// reported.
+///
+/// `A8MiniComplexCommand::SetCodecSpecs` has no frame rate field, so the frame rate of a requested spec is the
+/// one the stream is expected to already run at, and `A8Mini::set_codec` fails if it does not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CodecSpec {
+    stream_type: StreamType,
+    encoding: VideoEncoding,
+    width: u16,
+    height: u16,
+    bitrate_kbps: u16,
+    frame_rate: u8,
+}
+
+impl CodecSpec {
+    /// Lowest accepted bitrate in kbps.
+    pub const MIN_BITRATE_KBPS: u16 = 500;
+    /// Highest accepted bitrate in kbps.
+    pub const MAX_BITRATE_KBPS: u16 = 50_000;
+
+    /// Creates a spec, checking it against what the A8 mini supports:
+    ///
+    /// - 3840x2160 on the recording stream only, 1920x1080 and 1280x720 on every stream;
+    /// - 30 fps, or 60 fps up to 1920x1080;
+    /// - `MIN_BITRATE_KBPS` to `MAX_BITRATE_KBPS`.
+    ///
+    /// Returns `A8MiniError::UnsupportedCodec` otherwise.
+    pub fn new(
+        stream_type: StreamType,
+        encoding: VideoEncoding,
+        width: u16,
+        height: u16,
+        bitrate_kbps: u16,
+        frame_rate: u8,
+    ) -> Result<Self, A8MiniError> {
+        let unsupported = |reason: String| Err(A8MiniError::UnsupportedCodec(reason));
+
+        match (width, height) {
+            (3840, 2160) if stream_type != StreamType::Recording => {
+                return unsupported(format!("3840x2160 is only supported on the recording stream, not {:?}", stream_type));
+            }
+            (3840, 2160) | (1920, 1080) | (1280, 720) => {}
+            _ => return unsupported(format!("resolution {}x{} is not supported", width, height)),
+        }
+
+        match frame_rate {
+            30 => {}
+            60 if height <= 1080 => {}
+            _ => return unsupported(format!("{} fps is not supported at {}x{}", frame_rate, width, height)),
+        }
+
+        if !(Self::MIN_BITRATE_KBPS..=Self::MAX_BITRATE_KBPS).contains(&bitrate_kbps) {
+            return unsupported(format!(
+                "bitrate {} kbps is outside {} to {} kbps",
+                bitrate_kbps,
+                Self::MIN_BITRATE_KBPS,
+                Self::MAX_BITRATE_KBPS
+            ));
+        }
+
+        Ok(CodecSpec { stream_type, encoding, width, height, bitrate_kbps, frame_rate })
+    }
+
+    pub fn stream_type(&self) -> StreamType {
+        self.stream_type
+    }
+
+    pub fn encoding(&self) -> VideoEncoding {
+        self.encoding
+    }
+
+    /// Width and height in pixels.
+    pub fn resolution(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    pub fn bitrate_kbps(&self) -> u16 {
+        self.bitrate_kbps
+    }
+
+    pub fn frame_rate(&self) -> u8 {
+        self.frame_rate
+    }
+}
+
+/// Common resolutions, encoded as H.265 at a bitrate suited to the resolution.
+///
+/// All presets run at 30 fps: `A8MiniComplexCommand::SetCodecSpecs` cannot change the frame rate, so presets at
+/// other rates could never take effect.
+///
+/// Presets are turned into a `CodecSpec` for a given stream, replacing the former hardcoded 4K recording frame:
+///
+/// ```
+/// use a8mini_camera_rs::control::{A8MiniComplexCommand, StreamType, VideoPreset};
+///
+/// let spec = VideoPreset::Uhd4k30.spec(StreamType::Recording).unwrap();
+/// let command = A8MiniComplexCommand::SetCodecSpecs(spec);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VideoPreset {
+    /// 3840x2160 at 30 fps, recording stream only.
+    Uhd4k30,
+    /// 1920x1080 at 30 fps.
+    Fhd1080p30,
+    /// 1280x720 at 30 fps.
+    Hd720p30,
+}
+
+impl VideoPreset {
+    pub const ALL: [VideoPreset; 3] = [VideoPreset::Uhd4k30, VideoPreset::Fhd1080p30, VideoPreset::Hd720p30];
+
+    /// Width, height and frame rate.
+    pub fn format(&self) -> (u16, u16, u8) {
+        match self {
+            VideoPreset::Uhd4k30 => (3840, 2160, 30),
+            VideoPreset::Fhd1080p30 => (1920, 1080, 30),
+            VideoPreset::Hd720p30 => (1280, 720, 30),
+        }
+    }
+
+    /// Bitrate the preset is encoded at, in kbps.
+    pub fn bitrate_kbps(&self) -> u16 {
+        match self {
+            VideoPreset::Uhd4k30 => 15000,
+            VideoPreset::Fhd1080p30 => 4000,
+            VideoPreset::Hd720p30 => 2000,
+        }
+    }
+
+    /// Codec settings applying the preset to `stream_type`. Fails for `Uhd4k30` on the live streams.
+    pub fn spec(&self, stream_type: StreamType) -> Result<CodecSpec, A8MiniError> {
+        let (width, height, frame_rate) = self.format();
+        CodecSpec::new(stream_type, VideoEncoding::H265, width, height, self.bitrate_kbps(), frame_rate)
+    }
+
+    /// The preset with the resolution and frame rate of `spec`, if any.
+    pub fn from_spec(spec: &CodecSpec) -> Option<Self> {
+        let (width, height) = spec.resolution();
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.format() == (width, height, spec.frame_rate()))
+    }
+}
+
+impl fmt::Display for VideoPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VideoPreset::Uhd4k30 => write!(f, "4K30"),
+            VideoPreset::Fhd1080p30 => write!(f, "1080p30"),
+            VideoPreset::Hd720p30 => write!(f, "720p30"),
+        }
+    }
+}
+
+impl std::str::FromStr for VideoPreset {
+    type Err = A8MiniError;
+
+    /// Parses the names printed by `Display`, e.g. `4K30` or `720p30`, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| A8MiniError::UnsupportedCodec(format!("unknown video preset {}", s)))
+    }
+}
+
+/// Decodes a `StreamType` field of the reply to `cmd_id`.
+fn stream_type_from(cmd_id: u8, value: u8) -> Result<StreamType, FrameError> {
+    match value {
+        0 => Ok(StreamType::Recording),
+        1 => Ok(StreamType::Main),
+        2 => Ok(StreamType::Sub),
+        value => Err(FrameError::InvalidField { cmd_id, field: "stream type", value }),
+    }
+}
+
+impl Response for CodecSpec {
+    const CMD_ID: u8 = 0x20;
+
+    fn from_data(data: &[u8]) -> Result<Self, FrameError> {
+        expect_data_len(Self::CMD_ID, data, 9)?;
+
+        Ok(CodecSpec {
+            stream_type: stream_type_from(Self::CMD_ID, data[0])?,
+            encoding: match data[1] {
+                1 => VideoEncoding::H264,
+                2 => VideoEncoding::H265,
+                value => {
+                    return Err(FrameError::InvalidField { cmd_id: Self::CMD_ID, field: "video encoding", value })
+                }
+            },
+            width: u16::from_le_bytes([data[2], data[3]]),
+            height: u16::from_le_bytes([data[4], data[5]]),
+            bitrate_kbps: u16::from_le_bytes([data[6], data[7]]),
+            frame_rate: data[8],
+        })
+    }
+}
+
+/// Reply to `A8MiniComplexCommand::SetCodecSpecs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct A8MiniCodecAck {
+    pub stream_type: StreamType,
+    /// Whether the camera applied the settings.
+    pub accepted: bool,
+}
+
+impl Response for A8MiniCodecAck {
+    const CMD_ID: u8 = 0x21;
+
+    fn from_data(data: &[u8]) -> Result<Self, FrameError> {
+        expect_data_len(Self::CMD_ID, data, 2)?;
+        Ok(A8MiniCodecAck {
+            stream_type: stream_type_from(Self::CMD_ID, data[0])?,
+            accepted: data[1] == 1,
+        })
+    }
+}
+
+/// Reply to `A8MiniComplexCommand::SetTimeUTC`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct A8MiniTimeAck {
+    /// Whether the camera set its clock.
+    pub accepted: bool,
+}
+
+impl Response for A8MiniTimeAck {
+    const CMD_ID: u8 = 0x30;
+
+    fn from_data(data: &[u8]) -> Result<Self, FrameError> {
+        expect_data_len(Self::CMD_ID, data, 1)?;
+        Ok(A8MiniTimeAck { accepted: data[0] == 1 })
+    }
+}
+
+/// Maximum zoom multiple, replied to `A8MiniSimpleCommand::MaxZoomInformation`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct A8MiniMaxZoom {
+    pub zoom: f32,
+}
+
+impl Response for A8MiniMaxZoom {
+    const CMD_ID: u8 = 0x16;
+
+    fn from_data(data: &[u8]) -> Result<Self, FrameError> {
+        expect_data_len(Self::CMD_ID, data, 2)?;
+        Ok(A8MiniMaxZoom { zoom: zoom_from_bytes(data[0], data[1]) })
+    }
+}
+
+/// Current zoom multiple, replied to `A8MiniSimpleCommand::CurrentZoomInformation`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct A8MiniCurrentZoom {
+    pub zoom: f32,
+}
+
+impl Response for A8MiniCurrentZoom {
+    const CMD_ID: u8 = 0x18;
+
+    fn from_data(data: &[u8]) -> Result<Self, FrameError> {
+        expect_data_len(Self::CMD_ID, data, 2)?;
+        Ok(A8MiniCurrentZoom { zoom: zoom_from_bytes(data[0], data[1]) })
+    }
+}
+
+/// Zoom multiple reached after `A8MiniSimpleCommand::ZoomIn` or `ZoomOut`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct A8MiniManualZoom {
+    pub zoom: f32,
+}
+
+impl Response for A8MiniManualZoom {
+    const CMD_ID: u8 = 0x05;
+
+    fn from_data(data: &[u8]) -> Result<Self, FrameError> {
+        expect_data_len(Self::CMD_ID, data, 2)?;
+
+        // Sent as tenths of the zoom multiple
+        let zoom_tenths = u16::from_le_bytes([data[0], data[1]]);
+        Ok(A8MiniManualZoom { zoom: zoom_tenths as f32 / 10.0 })
+    }
+}
+
+/// Function feedback the camera pushes unsolicited (CMD_ID 0x0B), e.g. after `TakePicture` or `RecordVideo`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CameraEvent {
+    PhotoTaken,
+    /// Taking a photo failed, e.g. because no TF card is inserted or it is full.
+    PhotoFailed,
+    HdrOn,
+    HdrOff,
+    /// Recording failed, e.g. because no TF card is inserted or it is full.
+    RecordingFailed,
+    RecordingStarted,
+    RecordingStopped,
+    /// Feedback with a code this crate does not know.
+    Unknown(u8),
+}
+
+impl Response for CameraEvent {
+    const CMD_ID: u8 = 0x0b;
+
+    fn from_data(data: &[u8]) -> Result<Self, FrameError> {
+        expect_data_len(Self::CMD_ID, data, 1)?;
+
+        Ok(match data[0] {
+            0 => CameraEvent::PhotoTaken,
+            1 => CameraEvent::PhotoFailed,
+            2 => CameraEvent::HdrOn,
+            3 => CameraEvent::HdrOff,
+            4 => CameraEvent::RecordingFailed,
+            5 => CameraEvent::RecordingStarted,
+            6 => CameraEvent::RecordingStopped,
+            code => CameraEvent::Unknown(code),
+        })
+    }
+}
+
+/// A firmware version, packed by the camera into the low three bytes of a `u32` as `major.minor.patch`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FirmwareVersion {
+    pub major: u8,
+    pub minor: u8,
+    pub patch: u8,
+}
+
+impl FirmwareVersion {
+    /// Unpacks a version as sent by the camera. The highest byte is ignored.
+    pub fn from_raw(raw: u32) -> Self {
+        let [patch, minor, major, _] = raw.to_le_bytes();
+        FirmwareVersion { major, minor, patch }
+    }
+}
+
+impl fmt::Display for FirmwareVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Firmware versions of the camera's boards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct A8MiniFirmware {
+    pub camera: FirmwareVersion,
+    pub gimbal: FirmwareVersion,
+    /// `None` for cameras without a zoom board, such as the A8 mini.
+    pub zoom: Option<FirmwareVersion>,
+}
+
+impl Response for A8MiniFirmware {
+    const CMD_ID: u8 = 0x01;
+
+    fn from_data(data: &[u8]) -> Result<Self, FrameError> {
+        // Cameras without a zoom board may omit its version
+        if data.len() != 8 {
+            expect_data_len(Self::CMD_ID, data, 12)?;
+        }
+
+        let raw = |i: usize| u32::from_le_bytes([data[4 * i], data[4 * i + 1], data[4 * i + 2], data[4 * i + 3]]);
+        Ok(A8MiniFirmware {
+            camera: FirmwareVersion::from_raw(raw(0)),
+            gimbal: FirmwareVersion::from_raw(raw(1)),
+            zoom: Some(data.len())
+                .filter(|&len| len == 12)
+                .map(|_| raw(2))
+                .filter(|&zoom| zoom != 0)
+                .map(FirmwareVersion::from_raw),
+        })
+    }
+}
+
+/// Product model, encoded in the first two hex digits of the hardware ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProductModel {
+    ZR10,
+    A8Mini,
+    A2Mini,
+    ZR30,
+    ZT30,
+    ZT6,
+    /// A model this crate does not know, with its code.
+    Unknown(u8),
+}
+
+impl ProductModel {
+    /// Looks up the model with the given code.
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            0x6b => ProductModel::ZR10,
+            0x73 => ProductModel::A8Mini,
+            0x75 => ProductModel::A2Mini,
+            0x78 => ProductModel::ZR30,
+            0x7a => ProductModel::ZT30,
+            0x82 => ProductModel::ZT6,
+            code => ProductModel::Unknown(code),
+        }
+    }
+}
+
+impl fmt::Display for ProductModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProductModel::ZR10 => write!(f, "ZR10"),
+            ProductModel::A8Mini => write!(f, "A8 mini"),
+            ProductModel::A2Mini => write!(f, "A2 mini"),
+            ProductModel::ZR30 => write!(f, "ZR30"),
+            ProductModel::ZT30 => write!(f, "ZT30"),
+            ProductModel::ZT6 => write!(f, "ZT6"),
+            ProductModel::Unknown(code) => write!(f, "unknown model {:02x}", code),
+        }
+    }
+}
+
+/// Hardware ID of the camera.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct A8MiniHardwareId {
+    /// The 12 character ID as sent by the camera.
+    pub id: String,
+    /// `None` if the ID does not start with two hex digits.
+    pub model: Option<ProductModel>,
+}
+
+impl Response for A8MiniHardwareId {
+    const CMD_ID: u8 = 0x02;
+
+    fn from_data(data: &[u8]) -> Result<Self, FrameError> {
+        expect_data_len(Self::CMD_ID, data, 12)?;
+
+        let id = String::from_utf8_lossy(data).trim_end_matches('\0').to_string();
+        let model = id
+            .get(..2)
+            .and_then(|code| u8::from_str_radix(code, 16).ok())
+            .map(ProductModel::from_code);
+        Ok(A8MiniHardwareId { id, model })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -205,8 +947,9 @@ mod tests {
     #[test]
     fn test_complex_command_creation_angle() {
         let computed_command = A8MiniComplexCommand::SetYawPitchAngle(130, -20).to_bytes();
+        // Note: angles are little endian on the wire
         let expected_command: [u8; 14] = [
-            0x55, 0x66, 0x01, 0x04, 0x00, 0x00, 0x00, 0x0e, 0x00, 0x82, 0xff, 0xec, 0x8f, 0xad,
+            0x55, 0x66, 0x01, 0x04, 0x00, 0x00, 0x00, 0x0e, 0x82, 0x00, 0xec, 0xff, 0x97, 0xbc,
         ];
         assert_eq!(computed_command, expected_command);
     }
@@ -240,4 +983,274 @@ mod tests {
 
         assert_eq!(computed_attitude_info, expected_attitude_info);
     }
+
+    #[test]
+    fn test_attitude_from_reply_frame() {
+        // Attitude ACK as sent by the camera: CTRL 0x02, SEQ 42, CMD_ID 0x0d
+        let reply: &[u8] = &[
+            0x55, 0x66, 0x02, 0x0c, 0x00, 0x2a, 0x00, 0x0d, 0xf1, 0xff, 0x3c, 0xfe, 0x03, 0x00,
+            0x00, 0x00, 0xfe, 0xff, 0x01, 0x00, 0xec, 0x48,
+        ];
+
+        let frame = Frame::from_bytes(reply).unwrap();
+        let attitude = A8MiniAtittude::from_frame(&frame).unwrap();
+
+        assert_eq!(
+            attitude,
+            A8MiniAtittude {
+                theta_yaw: -15,
+                theta_pitch: -452,
+                theta_roll: 3,
+                v_yaw: 0,
+                v_pitch: -2,
+                v_roll: 1,
+            }
+        );
+        assert_eq!(attitude.yaw_deg(), -1.5);
+        assert_eq!(attitude.pitch_deg(), -45.2);
+        assert_eq!(attitude.roll_deg(), 0.3);
+        assert_eq!(attitude.v_pitch_deg(), -0.2);
+    }
+
+    #[test]
+    fn test_attitude_rejects_wrong_reply() {
+        let frame = Frame::new(0x0a, vec![0; 12]);
+        assert_eq!(
+            A8MiniAtittude::from_frame(&frame),
+            Err(FrameError::UnexpectedCmdId { expected: 0x0d, actual: 0x0a })
+        );
+
+        let frame = Frame::new(0x0d, vec![0; 10]);
+        assert_eq!(
+            A8MiniAtittude::from_frame(&frame),
+            Err(FrameError::MalformedPayload { cmd_id: 0x0d, expected: 12, actual: 10 })
+        );
+    }
+
+    #[test]
+    fn test_complex_command_creation_absolute_zoom() {
+        // Same zoom as the hardcoded ZoomMax entry
+        let frame = A8MiniComplexCommand::SetAbsoluteZoom(4.5).to_frame();
+        let zoom_max = A8MiniSimpleCommand::ZoomMax.to_frame();
+        assert_eq!((frame.cmd_id, frame.data), (zoom_max.cmd_id, zoom_max.data));
+
+        assert_eq!(A8MiniComplexCommand::SetAbsoluteZoom(2.96).to_frame().data, vec![3, 0]);
+        assert_eq!(A8MiniComplexCommand::SetAbsoluteZoom(0.5).to_frame().data, vec![1, 0]);
+        assert_eq!(A8MiniComplexCommand::SetAbsoluteZoom(42.0).to_frame().data, vec![30, 0]);
+    }
+
+    #[test]
+    fn test_complex_command_creation_focus() {
+        let computed_command = A8MiniComplexCommand::AutoFocusAt { x: 960, y: 540 }.to_frame();
+        assert_eq!(computed_command.cmd_id, 0x04);
+        assert_eq!(computed_command.data, vec![0x01, 0xc0, 0x03, 0x1c, 0x02]);
+
+        // Same payloads as the hardcoded FocusIn and FocusOut entries
+        for (direction, hardcoded) in [(5, A8MiniSimpleCommand::FocusIn), (-1, A8MiniSimpleCommand::FocusOut)] {
+            let frame = A8MiniComplexCommand::ManualFocus(direction).to_frame();
+            let hardcoded = hardcoded.to_frame();
+            assert_eq!((frame.cmd_id, frame.data), (hardcoded.cmd_id, hardcoded.data));
+        }
+        assert_eq!(A8MiniComplexCommand::ManualFocus(0).to_frame().data, vec![0]);
+    }
+
+    #[test]
+    fn test_complex_command_creation_utc_time() {
+        let timestamp: u64 = 1_700_000_000_123_456;
+        let bytes = A8MiniComplexCommand::SetTimeUTC(timestamp).to_bytes();
+
+        assert_eq!(bytes.len(), 18);
+        assert_eq!(&bytes[3..5], &[0x08, 0x00]);
+        assert_eq!(bytes[7], 0x30);
+        assert_eq!(&bytes[8..16], &timestamp.to_le_bytes());
+
+        let parsed = Frame::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed.data, timestamp.to_le_bytes());
+        assert!(A8MiniTimeAck::from_frame(&Frame::new(0x30, vec![1])).unwrap().accepted);
+    }
+
+    #[test]
+    fn test_codec_spec_validation() {
+        use StreamType::*;
+        use VideoEncoding::*;
+
+        assert!(CodecSpec::new(Recording, H265, 3840, 2160, 15000, 30).is_ok());
+        assert!(CodecSpec::new(Main, H264, 1920, 1080, 4000, 60).is_ok());
+        assert!(CodecSpec::new(Sub, H265, 1280, 720, 500, 30).is_ok());
+
+        for invalid in [
+            CodecSpec::new(Main, H265, 3840, 2160, 15000, 30),
+            CodecSpec::new(Recording, H265, 2560, 1440, 8000, 30),
+            CodecSpec::new(Recording, H265, 3840, 2160, 15000, 60),
+            CodecSpec::new(Main, H264, 1920, 1080, 4000, 25),
+            CodecSpec::new(Main, H264, 1920, 1080, 100, 30),
+            CodecSpec::new(Main, H264, 1920, 1080, 60000, 30),
+        ] {
+            assert!(matches!(invalid, Err(A8MiniError::UnsupportedCodec(_))));
+        }
+    }
+
+    #[test]
+    fn test_complex_command_creation_codec() {
+        let spec = CodecSpec::new(StreamType::Recording, VideoEncoding::H265, 3840, 2160, 15000, 30).unwrap();
+        let bytes = A8MiniComplexCommand::SetCodecSpecs(spec).to_bytes();
+
+        assert_eq!(&bytes[3..5], &[0x09, 0x00]);
+        assert_eq!(bytes[7], 0x21);
+        assert_eq!(&bytes[8..17], &[0x00, 0x02, 0x00, 0x0f, 0x70, 0x08, 0x98, 0x3a, 0x00]);
+        assert!(Frame::from_bytes(&bytes).is_ok());
+
+        assert_eq!(A8MiniComplexCommand::GetCodecSpecs(StreamType::Sub).to_frame().data, vec![2]);
+    }
+
+    #[test]
+    fn test_video_presets() {
+        // The former hardcoded Resolution4k frame
+        let resolution_4k = [
+            0x55, 0x66, 0x01, 0x09, 0x00, 0x00, 0x00, 0x21, 0x00, 0x02, 0x00, 0x0f, 0x70, 0x08, 0x98, 0x3a, 0x00, 0x70,
+            0xbe,
+        ];
+        let spec = VideoPreset::Uhd4k30.spec(StreamType::Recording).unwrap();
+        assert_eq!(A8MiniComplexCommand::SetCodecSpecs(spec).to_bytes(), resolution_4k);
+
+        for stream_type in [StreamType::Recording, StreamType::Main, StreamType::Sub] {
+            for preset in VideoPreset::ALL {
+                let Ok(spec) = preset.spec(stream_type) else {
+                    assert_eq!((preset, stream_type == StreamType::Recording), (VideoPreset::Uhd4k30, false));
+                    continue;
+                };
+                assert_eq!(VideoPreset::from_spec(&spec), Some(preset));
+                assert_eq!(preset.to_string().parse::<VideoPreset>().unwrap(), preset);
+            }
+        }
+
+        assert!(matches!("1440p30".parse::<VideoPreset>(), Err(A8MiniError::UnsupportedCodec(_))));
+    }
+
+    #[test]
+    fn test_codec_replies_from_data() {
+        let spec = CodecSpec::from_data(&[1, 1, 0x80, 0x07, 0x38, 0x04, 0xa0, 0x0f, 60]).unwrap();
+        assert_eq!(spec, CodecSpec::new(StreamType::Main, VideoEncoding::H264, 1920, 1080, 4000, 60).unwrap());
+
+        assert!(matches!(
+            CodecSpec::from_data(&[3, 1, 0, 0, 0, 0, 0, 0, 30]),
+            Err(FrameError::InvalidField { field: "stream type", value: 3, .. })
+        ));
+        assert!(matches!(
+            CodecSpec::from_data(&[0, 0, 0, 0, 0, 0, 0, 0, 30]),
+            Err(FrameError::InvalidField { field: "video encoding", value: 0, .. })
+        ));
+
+        let ack = A8MiniCodecAck::from_data(&[2, 1]).unwrap();
+        assert_eq!(ack, A8MiniCodecAck { stream_type: StreamType::Sub, accepted: true });
+        assert!(!A8MiniCodecAck::from_data(&[0, 0]).unwrap().accepted);
+    }
+
+    #[test]
+    fn test_zoom_replies_from_data() {
+        assert_eq!(A8MiniMaxZoom::from_data(&[6, 0]).unwrap().zoom, 6.0);
+        assert_eq!(A8MiniMaxZoom::from_data(&[4, 5]).unwrap().zoom, 4.5);
+        assert_eq!(A8MiniCurrentZoom::from_data(&[2, 3]).unwrap().zoom, 2.3);
+        assert_eq!(A8MiniManualZoom::from_data(&[0x2d, 0x00]).unwrap().zoom, 4.5);
+        assert!(A8MiniCurrentZoom::from_frame(&Frame::new(0x16, vec![2, 3])).is_err());
+        assert!(A8MiniAbsoluteZoomAck::from_data(&[1]).unwrap().accepted);
+        assert!(!A8MiniAbsoluteZoomAck::from_data(&[0]).unwrap().accepted);
+    }
+
+    #[test]
+    fn test_gimbal_mode_commands() {
+        let modes = [(GimbalMode::Lock, 3), (GimbalMode::Follow, 4), (GimbalMode::Fpv, 5)];
+        for (mode, function) in modes {
+            let frame = A8MiniSimpleCommand::from(mode).to_frame();
+            assert_eq!((frame.cmd_id, frame.data), (0x0c, vec![function]));
+        }
+    }
+
+    #[test]
+    fn test_gimbal_config_from_data() {
+        let config = GimbalConfig::from_data(&[0, 1, 0, 1, 2, 2, 0]).unwrap();
+        assert_eq!(
+            config,
+            GimbalConfig {
+                hdr_enabled: Some(true),
+                recording: RecordingState::Recording,
+                motion_mode: Some(GimbalMode::Fpv),
+                mounting_direction: Some(MountingDirection::UpsideDown),
+                video_output: VideoOutput::Hdmi,
+            }
+        );
+        assert!(config.is_recording());
+
+        let config = GimbalConfig::from_data(&[0, 0, 0, 2, 0, 0, 1]).unwrap();
+        assert_eq!(config.recording, RecordingState::NoTfCard);
+        assert_eq!(config.mounting_direction, None);
+        assert!(!config.is_recording());
+
+        let config = GimbalConfig::from_data(&[0, 0, 0, 0, 0, 1, 2]).unwrap();
+        assert_eq!(config.video_output, VideoOutput::Off);
+
+        // Unknown values do not fail the rest of the status
+        let config = GimbalConfig::from_data(&[0, 7, 0, 9, 3, 1, 5]).unwrap();
+        assert_eq!(config.hdr_enabled, None);
+        assert_eq!(config.recording, RecordingState::Unknown(9));
+        assert_eq!(config.motion_mode, None);
+        assert_eq!(config.mounting_direction, Some(MountingDirection::Normal));
+        assert_eq!(config.video_output, VideoOutput::Unknown(5));
+    }
+
+    #[test]
+    fn test_camera_event_from_frame() {
+        let frame = Frame::new(0x0b, vec![5]);
+        assert_eq!(CameraEvent::from_frame(&frame), Ok(CameraEvent::RecordingStarted));
+        assert_eq!(CameraEvent::from_data(&[1]), Ok(CameraEvent::PhotoFailed));
+        assert_eq!(CameraEvent::from_data(&[42]), Ok(CameraEvent::Unknown(42)));
+        assert!(CameraEvent::from_data(&[]).is_err());
+    }
+
+    #[test]
+    fn test_firmware_from_data() {
+        let mut data = Vec::new();
+        for raw in [0x6e03_0203u32, 0x6e03_0101, 0x0000_0000] {
+            data.extend_from_slice(&raw.to_le_bytes());
+        }
+
+        let firmware = A8MiniFirmware::from_data(&data).unwrap();
+        assert_eq!(firmware.camera, FirmwareVersion { major: 3, minor: 2, patch: 3 });
+        assert_eq!(firmware.gimbal.to_string(), "3.1.1");
+        assert_eq!(firmware.zoom, None);
+        assert!(firmware.gimbal < firmware.camera);
+
+        assert_eq!(A8MiniFirmware::from_data(&data[..8]).unwrap(), firmware);
+        assert!(A8MiniFirmware::from_data(&data[..10]).is_err());
+    }
+
+    #[test]
+    fn test_hardware_id_from_data() {
+        let hardware_id = A8MiniHardwareId::from_data(b"7300000000ab").unwrap();
+        assert_eq!(hardware_id.id, "7300000000ab");
+        assert_eq!(hardware_id.model, Some(ProductModel::A8Mini));
+
+        let hardware_id = A8MiniHardwareId::from_data(b"7a1234\0\0\0\0\0\0").unwrap();
+        assert_eq!(hardware_id.id, "7a1234");
+        assert_eq!(hardware_id.model, Some(ProductModel::ZT30));
+
+        assert_eq!(A8MiniHardwareId::from_data(b"ff0000000000").unwrap().model, Some(ProductModel::Unknown(0xff)));
+        assert_eq!(A8MiniHardwareId::from_data(b"zz0000000000").unwrap().model, None);
+    }
+
+    #[test]
+    fn test_http_query_urls() {
+        assert_eq!(
+            A8MiniSimpleHTTPQuery::GetMediaCountPhotos.to_string(),
+            "http://192.168.144.25:82/cgi-bin/media.cgi/api/v1/getmediacount?media_type=0&path=101SIYI_IMG"
+        );
+        assert_eq!(
+            A8MiniComplexHTTPQuery::GetVideo(7).to_url(&http_base_url("10.0.0.7", "8082")),
+            "http://10.0.0.7:8082/photo/100SIYI_VID/REC_0007.mp4"
+        );
+        assert_eq!(
+            A8MiniComplexHTTPQuery::GetPhoto(12).to_url("http://10.0.0.7/"),
+            "http://10.0.0.7/photo/101SIYI_IMG/IMG_0012.jpg"
+        );
+    }
 }
@@ -0,0 +1,236 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use tokio::net::UdpSocket;
+use tokio::sync::{broadcast, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{debug, trace, warn};
+
+use crate::constants;
+use crate::control::{CameraEvent, Response};
+use crate::frame::{Frame, FrameError};
+
+/// A reply, or why the datagram answering a request could not be decoded.
+pub(crate) type Reply = Result<Frame, FrameError>;
+
+/// A request waiting for its reply.
+#[derive(Debug)]
+struct Pending {
+    seq: u16,
+    reply_tx: oneshot::Sender<Reply>,
+}
+
+/// Routes inbound frames to the requests waiting on them, keyed by CMD_ID. Function feedback frames, which answer
+/// no request, are broadcast as `CameraEvent`s instead. Datagrams that fail to decode but whose header is intact
+/// complete the request they answer with the decode error.
+#[derive(Debug)]
+pub(crate) struct Dispatcher {
+    pending: Mutex<HashMap<u8, VecDeque<Pending>>>,
+    last_received: Mutex<Option<Instant>>,
+    events: broadcast::Sender<CameraEvent>,
+}
+
+impl Default for Dispatcher {
+    fn default() -> Self {
+        Dispatcher {
+            pending: Mutex::default(),
+            last_received: Mutex::default(),
+            events: broadcast::channel(32).0,
+        }
+    }
+}
+
+impl Dispatcher {
+    /// Registers `request` as waiting for a reply. Must be called before the request is sent.
+    pub(crate) fn register(&self, request: &Frame) -> oneshot::Receiver<Reply> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+
+        self.pending
+            .lock()
+            .unwrap()
+            .entry(request.cmd_id)
+            .or_default()
+            .push_back(Pending {
+                seq: request.seq,
+                reply_tx,
+            });
+
+        reply_rx
+    }
+
+    /// Removes `request` from the pending requests, e.g. after it timed out.
+    pub(crate) fn unregister(&self, request: &Frame) {
+        if let Some(queue) = self.pending.lock().unwrap().get_mut(&request.cmd_id) {
+            queue.retain(|pending| pending.seq != request.seq);
+        }
+    }
+
+    /// Hands `reply` to the request it answers. Replies must carry the request's CMD_ID. The request with the
+    /// reply's SEQ receives it if there is one, otherwise the oldest request with the same CMD_ID does, since some
+    /// firmware keeps its own SEQ counter instead of echoing ours.
+    ///
+    /// Returns the reply back if no request is waiting for it.
+    pub(crate) fn dispatch(&self, reply: Frame) -> Result<(), Frame> {
+        let Some(request) = self.take(reply.cmd_id, reply.seq) else {
+            return Err(reply);
+        };
+
+        request.reply_tx.send(Ok(reply)).map_err(|reply| reply.unwrap())
+    }
+
+    /// Completes the request a corrupted reply with `cmd_id` and `seq` answers with `error`, matched like
+    /// `dispatch`. Returns false if no request is waiting for it.
+    pub(crate) fn fail(&self, cmd_id: u8, seq: u16, error: FrameError) -> bool {
+        match self.take(cmd_id, seq) {
+            Some(request) => request.reply_tx.send(Err(error)).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Removes and returns the request a reply with `cmd_id` and `seq` answers.
+    fn take(&self, cmd_id: u8, seq: u16) -> Option<Pending> {
+        let mut pending = self.pending.lock().unwrap();
+        let queue = pending.get_mut(&cmd_id)?;
+        queue.retain(|pending| !pending.reply_tx.is_closed());
+
+        let index = queue.iter().position(|pending| pending.seq == seq).unwrap_or(0);
+        queue.remove(index)
+    }
+
+    /// Subscribes to the `CameraEvent`s received from now on.
+    pub(crate) fn subscribe_events(&self) -> broadcast::Receiver<CameraEvent> {
+        self.events.subscribe()
+    }
+
+    /// Broadcasts `frame` if it carries function feedback. Returns the frame back otherwise.
+    fn publish_event(&self, frame: Frame) -> Result<(), Frame> {
+        if frame.cmd_id != CameraEvent::CMD_ID {
+            return Err(frame);
+        }
+
+        match CameraEvent::from_frame(&frame) {
+            Ok(event) => {
+                debug!(?event, "received camera event");
+                let _ = self.events.send(event);
+            }
+            Err(e) => warn!(error = %e, "discarding malformed function feedback"),
+        }
+        Ok(())
+    }
+
+    /// When the last well-formed frame was received, whether or not a request was waiting for it.
+    pub(crate) fn last_received(&self) -> Option<Instant> {
+        *self.last_received.lock().unwrap()
+    }
+
+    /// Spawns the task reading frames from `socket` and dispatching them until aborted.
+    pub(crate) fn spawn_reader(self: &Arc<Self>, socket: Arc<UdpSocket>) -> JoinHandle<()> {
+        let dispatcher = Arc::clone(self);
+
+        tokio::spawn(async move {
+            let mut recv_buffer = [0; constants::RECV_BUFF_SIZE];
+
+            loop {
+                let recv_len = match socket.recv(&mut recv_buffer).await {
+                    Ok(recv_len) => recv_len,
+                    Err(e) => {
+                        warn!(error = %e, "receive failed");
+                        continue;
+                    }
+                };
+
+                trace!(bytes = ?format_args!("{:02x?}", &recv_buffer[..recv_len]), "received datagram");
+
+                let reply = match Frame::from_bytes(&recv_buffer[..recv_len]) {
+                    Ok(reply) => reply,
+                    Err(e) => {
+                        warn!(error = %e, len = recv_len, "received malformed frame");
+                        let failed = Frame::peek_header(&recv_buffer[..recv_len])
+                            .is_some_and(|(cmd_id, seq)| dispatcher.fail(cmd_id, seq, e));
+                        if !failed {
+                            debug!(len = recv_len, "discarding malformed frame no request is waiting for");
+                        }
+                        continue;
+                    }
+                };
+                *dispatcher.last_received.lock().unwrap() = Some(Instant::now());
+
+                let unmatched = dispatcher
+                    .dispatch(reply)
+                    .or_else(|reply| dispatcher.publish_event(reply));
+                if let Err(reply) = unmatched {
+                    debug!(
+                        cmd_id = reply.cmd_id,
+                        seq = reply.seq,
+                        "discarding frame no request is waiting for"
+                    );
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dispatch_by_cmd_id_and_seq() {
+        let dispatcher = Dispatcher::default();
+
+        let attitude = Frame::new(0x0d, vec![]).with_seq(1);
+        let status = Frame::new(0x0a, vec![]).with_seq(2);
+        let mut attitude_rx = dispatcher.register(&attitude);
+        let mut status_rx = dispatcher.register(&status);
+
+        // No request is waiting on this CMD_ID
+        assert!(dispatcher.dispatch(Frame::new(0x16, vec![])).is_err());
+
+        // Firmware that does not echo SEQ: the oldest request with the CMD_ID receives the reply
+        dispatcher.dispatch(Frame::new(0x0a, vec![1]).with_seq(0)).unwrap();
+        assert_eq!(status_rx.try_recv().unwrap().unwrap().data, vec![1]);
+
+        // An exact SEQ match wins over older requests with the CMD_ID
+        let attitude_2 = Frame::new(0x0d, vec![]).with_seq(3);
+        let mut attitude_2_rx = dispatcher.register(&attitude_2);
+        dispatcher.dispatch(Frame::new(0x0d, vec![3]).with_seq(3)).unwrap();
+        assert_eq!(attitude_2_rx.try_recv().unwrap().unwrap().data, vec![3]);
+        assert!(attitude_rx.try_recv().is_err());
+
+        // A SEQ that matched by chance does not turn away later replies with unrelated SEQs
+        dispatcher.dispatch(Frame::new(0x0d, vec![9]).with_seq(7)).unwrap();
+        assert_eq!(attitude_rx.try_recv().unwrap().unwrap().data, vec![9]);
+
+        let attitude_3 = Frame::new(0x0d, vec![]).with_seq(4);
+        let mut attitude_3_rx = dispatcher.register(&attitude_3);
+        dispatcher.unregister(&attitude_3);
+        assert!(dispatcher.dispatch(Frame::new(0x0d, vec![4]).with_seq(4)).is_err());
+        assert!(attitude_3_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_fail_with_decode_error() {
+        let dispatcher = Dispatcher::default();
+        let error = FrameError::CrcMismatch { expected: 1, computed: 2 };
+
+        let attitude = Frame::new(0x0d, vec![]).with_seq(4);
+        let mut attitude_rx = dispatcher.register(&attitude);
+
+        assert!(!dispatcher.fail(0x0a, 4, error.clone()));
+        assert!(dispatcher.fail(0x0d, 4, error.clone()));
+        assert_eq!(attitude_rx.try_recv().unwrap(), Err(error.clone()));
+        assert!(!dispatcher.fail(0x0d, 4, error));
+    }
+
+    #[test]
+    fn test_publish_function_feedback() {
+        let dispatcher = Dispatcher::default();
+        let mut events = dispatcher.subscribe_events();
+
+        assert!(dispatcher.publish_event(Frame::new(0x0b, vec![0])).is_ok());
+        assert!(dispatcher.publish_event(Frame::new(0x0d, vec![0; 12])).is_err());
+        assert_eq!(events.try_recv().unwrap(), CameraEvent::PhotoTaken);
+        assert!(events.try_recv().is_err());
+    }
+}
@@ -0,0 +1,420 @@
+//! A software stand-in for the A8 mini that speaks the SIYI SDK protocol over UDP.
+//!
+//! The emulator keeps a simulated gimbal and camera state and answers ACK-bearing commands with correctly framed
+//! replies, followed by function feedback for photo, HDR and recording functions, so `A8Mini` can be exercised
+//! without hardware. A `MediaServer` sharing the same state stands in for the
+//! camera's HTTP media API:
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! use a8mini_camera_rs::{emulator::Emulator, A8Mini};
+//!
+//! let emulator = Emulator::bind("127.0.0.1:0").await?;
+//! let port = emulator.local_addr()?.port().to_string();
+//! emulator.media_server("127.0.0.1:0").await?.spawn();
+//! emulator.spawn();
+//!
+//! let cam = A8Mini::connect_to("127.0.0.1", &port, "82", "0", "0").await?;
+//! println!("{:?}", cam.get_attitude_information().await?);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use tokio::net::{ToSocketAddrs, UdpSocket};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{debug, trace, warn};
+
+use crate::constants;
+use crate::frame::Frame;
+
+mod media;
+
+pub use media::{synthetic_jpeg, synthetic_mp4, MediaServer};
+
+/// CTRL value the camera uses for ACK frames.
+const CTRL_ACK: u8 = 0x02;
+/// CMD_ID of the function feedback pushed after photo, HDR and recording functions.
+const CMD_FUNCTION_FEEDBACK: u8 = 0x0b;
+
+/// Simulated state of the emulated camera and gimbal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmulatorState {
+    /// Yaw, pitch and roll in degrees.
+    pub attitude: [f32; 3],
+    /// Yaw, pitch and roll velocities in degrees per second.
+    pub velocity: [f32; 3],
+    /// Current zoom multiple.
+    pub zoom: f32,
+    /// Maximum zoom multiple.
+    pub max_zoom: f32,
+    /// Focus position, moved by manual focus commands.
+    pub focus: i32,
+    /// Video pixel of the last auto focus, if one was given.
+    pub focus_point: Option<(u16, u16)>,
+    pub recording: bool,
+    /// Whether a TF card is inserted. Photos and recordings fail without one.
+    pub tf_card: bool,
+    pub hdr: bool,
+    /// Gimbal motion mode: 0 lock, 1 follow, 2 FPV.
+    pub motion_mode: u8,
+    /// Video output: 0 HDMI, 1 CVBS, 2 off.
+    pub video_output: u8,
+    /// Number of photos taken.
+    pub photo_count: u32,
+    /// Number of videos recorded.
+    pub video_count: u32,
+    /// Codec settings per stream type (recording, main stream, sub stream): encoding, width, height, bitrate,
+    /// frame rate.
+    pub codecs: [(u8, u16, u16, u16, u8); 3],
+    /// Last UTC time pushed by the host, in microseconds.
+    pub utc_time_us: Option<u64>,
+    /// Firmware versions of the camera, gimbal and zoom boards.
+    pub firmware_versions: [u32; 3],
+    /// Hardware ID string.
+    pub hardware_id: [u8; 12],
+}
+
+impl Default for EmulatorState {
+    fn default() -> Self {
+        EmulatorState {
+            attitude: [0.0; 3],
+            velocity: [0.0; 3],
+            zoom: 1.0,
+            max_zoom: 6.0,
+            focus: 0,
+            focus_point: None,
+            recording: false,
+            tf_card: true,
+            hdr: false,
+            motion_mode: 1,
+            video_output: 0,
+            photo_count: 0,
+            video_count: 0,
+            codecs: [
+                (2, 3840, 2160, 15000, 30),
+                (2, 1920, 1080, 4000, 30),
+                (2, 1280, 720, 2000, 30),
+            ],
+            utc_time_us: None,
+            firmware_versions: [0x6e03_0203, 0x6e03_0101, 0x6e01_0002],
+            hardware_id: *b"7300000000ab",
+        }
+    }
+}
+
+impl EmulatorState {
+    /// Advances the gimbal by `dt_secs` at its current velocity, keeping it within its mechanical limits.
+    fn advance(&mut self, dt_secs: f32) {
+        for (angle, velocity) in self.attitude.iter_mut().zip(self.velocity) {
+            *angle += velocity * dt_secs;
+        }
+
+        self.attitude[0] = self.attitude[0].clamp(-135.0, 135.0);
+        self.attitude[1] = self.attitude[1].clamp(-90.0, 25.0);
+    }
+
+    /// Applies `request` and returns the DATA of the reply, if the command has one.
+    fn handle(&mut self, request: &Frame) -> Option<Vec<u8>> {
+        let data = &request.data;
+        let arg = |i: usize| data.get(i).copied().unwrap_or(0);
+        let arg_u16 = |i: usize| u16::from_le_bytes([arg(i), arg(i + 1)]);
+
+        match request.cmd_id {
+            // Firmware version
+            0x01 => Some(
+                self.firmware_versions
+                    .iter()
+                    .flat_map(|version| version.to_le_bytes())
+                    .collect(),
+            ),
+            // Hardware ID
+            0x02 => Some(self.hardware_id.to_vec()),
+            // Auto focus
+            0x04 => {
+                self.focus_point = (data.len() >= 5).then(|| (arg_u16(1), arg_u16(3)));
+                Some(vec![1])
+            }
+            // Manual zoom
+            0x05 => {
+                let step = arg(0) as i8;
+                self.zoom = (self.zoom + 0.1 * step.signum() as f32).clamp(1.0, self.max_zoom);
+                Some(zoom_tenths(self.zoom).to_le_bytes().to_vec())
+            }
+            // Manual focus
+            0x06 => {
+                self.focus += (arg(0) as i8).signum() as i32;
+                Some(vec![1])
+            }
+            // Gimbal rotation speed, 1 unit is 1 degree per second
+            0x07 => {
+                self.velocity = [arg(0) as i8 as f32, arg(1) as i8 as f32, 0.0];
+                Some(vec![1])
+            }
+            // Center
+            0x08 => {
+                self.attitude = [0.0; 3];
+                self.velocity = [0.0; 3];
+                Some(vec![1])
+            }
+            // Gimbal configuration information
+            0x0a => Some(vec![
+                0,
+                self.hdr as u8,
+                0,
+                match (self.tf_card, self.recording) {
+                    (false, _) => 2,
+                    (true, recording) => recording as u8,
+                },
+                self.motion_mode,
+                1,
+                self.video_output,
+            ]),
+            // Photo and video functions, which are not acknowledged
+            0x0c => {
+                match arg(0) {
+                    0 if self.tf_card => self.photo_count += 1,
+                    1 => self.hdr = !self.hdr,
+                    2 if self.tf_card => {
+                        if self.recording {
+                            self.video_count += 1;
+                        }
+                        self.recording = !self.recording;
+                    }
+                    mode @ 3..=5 => self.motion_mode = mode - 3,
+                    output @ 6..=8 => self.video_output = output - 6,
+                    _ => {}
+                }
+                None
+            }
+            // Attitude
+            0x0d => Some(
+                self.attitude
+                    .iter()
+                    .chain(self.velocity.iter())
+                    .flat_map(|value| tenths(*value).to_le_bytes())
+                    .collect(),
+            ),
+            // Set gimbal angles
+            0x0e => {
+                self.attitude[0] = arg_u16(0) as i16 as f32 / 10.0;
+                self.attitude[1] = arg_u16(2) as i16 as f32 / 10.0;
+                self.velocity = [0.0; 3];
+                self.advance(0.0);
+                Some(
+                    self.attitude
+                        .iter()
+                        .flat_map(|value| tenths(*value).to_le_bytes())
+                        .collect(),
+                )
+            }
+            // Absolute zoom
+            0x0f => {
+                let zoom = arg(0) as f32 + arg(1) as f32 / 10.0;
+                self.zoom = zoom.clamp(1.0, self.max_zoom);
+                Some(vec![1])
+            }
+            // Maximum zoom
+            0x16 => {
+                let max_zoom = zoom_tenths(self.max_zoom);
+                Some(vec![(max_zoom / 10) as u8, (max_zoom % 10) as u8])
+            }
+            // Current zoom
+            0x18 => {
+                let zoom = zoom_tenths(self.zoom);
+                Some(vec![(zoom / 10) as u8, (zoom % 10) as u8])
+            }
+            // Get codec specs
+            0x20 => {
+                let stream_type = arg(0).min(2);
+                let (encoding, width, height, bitrate, frame_rate) = self.codecs[stream_type as usize];
+                let mut reply = vec![stream_type, encoding];
+                reply.extend_from_slice(&width.to_le_bytes());
+                reply.extend_from_slice(&height.to_le_bytes());
+                reply.extend_from_slice(&bitrate.to_le_bytes());
+                reply.push(frame_rate);
+                Some(reply)
+            }
+            // Set codec specs
+            0x21 => {
+                let stream_type = arg(0).min(2);
+                let codec = &mut self.codecs[stream_type as usize];
+                *codec = (arg(1), arg_u16(2), arg_u16(4), arg_u16(6), codec.4);
+                Some(vec![stream_type, 1])
+            }
+            // Set UTC time
+            0x30 => {
+                let mut timestamp = [0; 8];
+                for (i, byte) in timestamp.iter_mut().enumerate() {
+                    *byte = arg(i);
+                }
+                self.utc_time_us = Some(u64::from_le_bytes(timestamp));
+                Some(vec![1])
+            }
+            // Reboot
+            0x80 => {
+                if arg(1) == 1 {
+                    self.attitude = [0.0; 3];
+                    self.velocity = [0.0; 3];
+                }
+                Some(vec![arg(0), arg(1)])
+            }
+            _ => None,
+        }
+    }
+
+    /// Function feedback code pushed after `request` was applied, if any.
+    fn feedback(&self, request: &Frame) -> Option<u8> {
+        if request.cmd_id != 0x0c {
+            return None;
+        }
+
+        match request.data.first()? {
+            0 if self.tf_card => Some(0),
+            0 => Some(1),
+            1 if self.hdr => Some(2),
+            1 => Some(3),
+            2 if !self.tf_card => Some(4),
+            2 if self.recording => Some(5),
+            2 => Some(6),
+            _ => None,
+        }
+    }
+}
+
+/// Converts a value to tenths, as used on the wire for angles and velocities.
+fn tenths(value: f32) -> i16 {
+    (value * 10.0).round() as i16
+}
+
+/// Converts a zoom multiple to tenths.
+fn zoom_tenths(zoom: f32) -> u16 {
+    (zoom * 10.0).round() as u16
+}
+
+/// An emulated A8 mini listening on a UDP socket.
+#[derive(Debug)]
+pub struct Emulator {
+    socket: UdpSocket,
+    state: Arc<Mutex<EmulatorState>>,
+}
+
+impl Emulator {
+    /// Binds the emulator's command socket to `addr`. Use port 0 to pick a free port.
+    pub async fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        Ok(Emulator {
+            socket: UdpSocket::bind(addr).await?,
+            state: Arc::new(Mutex::new(EmulatorState::default())),
+        })
+    }
+
+    /// Address the command socket is bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Shared handle to the simulated state, for inspection or to stage scenarios.
+    pub fn state(&self) -> Arc<Mutex<EmulatorState>> {
+        Arc::clone(&self.state)
+    }
+
+    /// Binds a `MediaServer` to `addr` serving this emulator's media.
+    pub async fn media_server<A: ToSocketAddrs>(&self, addr: A) -> io::Result<MediaServer> {
+        MediaServer::bind(addr, self.state()).await
+    }
+
+    /// Serves requests until the socket fails.
+    pub async fn run(self) -> io::Result<()> {
+        let mut recv_buffer = [0; constants::RECV_BUFF_SIZE];
+        let mut last_update = Instant::now();
+
+        loop {
+            let (recv_len, peer) = self.socket.recv_from(&mut recv_buffer).await?;
+            trace!(bytes = ?format_args!("{:02x?}", &recv_buffer[..recv_len]), %peer, "received datagram");
+
+            let request = match Frame::from_bytes(&recv_buffer[..recv_len]) {
+                Ok(request) => request,
+                Err(e) => {
+                    warn!(error = %e, %peer, "discarding malformed frame");
+                    continue;
+                }
+            };
+
+            let (reply_data, feedback) = {
+                let mut state = self.state.lock().unwrap();
+                state.advance(last_update.elapsed().as_secs_f32());
+                last_update = Instant::now();
+                let reply_data = state.handle(&request);
+                (reply_data, state.feedback(&request))
+            };
+            debug!(cmd_id = request.cmd_id, seq = request.seq, ack = reply_data.is_some(), "handled request");
+
+            if let Some(data) = reply_data {
+                let mut reply = Frame::new(request.cmd_id, data).with_seq(request.seq);
+                reply.ctrl = CTRL_ACK;
+                self.socket.send_to(&reply.to_bytes(), peer).await?;
+            }
+
+            if let Some(code) = feedback {
+                let event = Frame::new(CMD_FUNCTION_FEEDBACK, vec![code]);
+                self.socket.send_to(&event.to_bytes(), peer).await?;
+            }
+        }
+    }
+
+    /// Serves requests on a background task.
+    pub fn spawn(self) -> JoinHandle<io::Result<()>> {
+        tokio::spawn(self.run())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::{A8MiniComplexCommand, A8MiniSimpleCommand, Command};
+
+    #[test]
+    fn test_emulator_state_transitions() {
+        let mut state = EmulatorState::default();
+
+        let take_picture = A8MiniSimpleCommand::TakePicture.to_frame();
+        assert_eq!(state.handle(&take_picture), None);
+        assert_eq!(state.photo_count, 1);
+        assert_eq!(state.feedback(&take_picture), Some(0));
+
+        let record_video = A8MiniSimpleCommand::RecordVideo.to_frame();
+        state.handle(&record_video);
+        assert!(state.recording);
+        assert_eq!(state.feedback(&record_video), Some(5));
+        state.handle(&record_video);
+        assert!(!state.recording);
+        assert_eq!(state.video_count, 1);
+        assert_eq!(state.feedback(&record_video), Some(6));
+
+        state.tf_card = false;
+        state.handle(&take_picture);
+        state.handle(&record_video);
+        assert_eq!((state.photo_count, state.recording), (1, false));
+        assert_eq!(state.feedback(&take_picture), Some(1));
+        assert_eq!(state.feedback(&record_video), Some(4));
+        state.tf_card = true;
+
+        state.handle(&A8MiniSimpleCommand::SetLockMode.to_frame());
+        assert_eq!(state.motion_mode, 0);
+
+        assert_eq!(state.handle(&A8MiniSimpleCommand::ZoomIn.to_frame()), Some(vec![11, 0]));
+        assert_eq!(state.handle(&A8MiniSimpleCommand::ZoomMax.to_frame()), Some(vec![1]));
+        assert_eq!(state.zoom, 4.5);
+
+        let reply = state.handle(&A8MiniComplexCommand::SetYawPitchAngle(900, -450).to_frame());
+        assert_eq!(reply, Some(vec![0x84, 0x03, 0x3e, 0xfe, 0x00, 0x00]));
+
+        state.handle(&A8MiniComplexCommand::SetYawPitchSpeed(10, 0).to_frame());
+        state.advance(10.0);
+        assert_eq!(state.attitude, [135.0, -45.0, 0.0]);
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use super::EmulatorState;
+use crate::control::{HTTPResponse, HTTPResponseData};
+
+const PHOTO_DIR: &str = "101SIYI_IMG";
+const VIDEO_DIR: &str = "100SIYI_VID";
+
+/// An emulated camera HTTP server serving the media API and synthetic media files for an `Emulator`'s state.
+#[derive(Debug)]
+pub struct MediaServer {
+    listener: TcpListener,
+    state: Arc<Mutex<EmulatorState>>,
+}
+
+impl MediaServer {
+    /// Binds the media server to `addr`, serving media from `state`. Use port 0 to pick a free port.
+    pub async fn bind<A: ToSocketAddrs>(addr: A, state: Arc<Mutex<EmulatorState>>) -> io::Result<Self> {
+        Ok(MediaServer {
+            listener: TcpListener::bind(addr).await?,
+            state,
+        })
+    }
+
+    /// Address the server is bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Serves connections until accepting fails.
+    pub async fn run(self) -> io::Result<()> {
+        loop {
+            let (stream, peer) = self.listener.accept().await?;
+            let state = Arc::clone(&self.state);
+
+            tokio::spawn(async move {
+                if let Err(e) = serve(stream, state).await {
+                    warn!(error = %e, %peer, "HTTP connection failed");
+                }
+            });
+        }
+    }
+
+    /// Serves connections on a background task.
+    pub fn spawn(self) -> JoinHandle<io::Result<()>> {
+        tokio::spawn(self.run())
+    }
+}
+
+/// Serves a single request on `stream`.
+async fn serve(mut stream: TcpStream, state: Arc<Mutex<EmulatorState>>) -> io::Result<()> {
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+        let read_len = stream.read(&mut buf).await?;
+        if read_len == 0 {
+            return Ok(());
+        }
+        request.extend_from_slice(&buf[..read_len]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let target = request.split_whitespace().nth(1).unwrap_or("/");
+    let (status, content_type, body) = route(target, &state.lock().unwrap());
+    debug!(target, status, len = body.len(), "served HTTP request");
+
+    let header = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    stream.write_all(header.as_bytes()).await?;
+    stream.write_all(&body).await?;
+    stream.shutdown().await
+}
+
+/// Produces the status line, content type and body for a request target.
+fn route(target: &str, state: &EmulatorState) -> (&'static str, &'static str, Vec<u8>) {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let params: HashMap<&str, &str> = query
+        .split('&')
+        .filter_map(|param| param.split_once('='))
+        .collect();
+    let media_type: i32 = params.get("media_type").and_then(|t| t.parse().ok()).unwrap_or(0);
+    let (dir, count) = match media_type {
+        0 => (PHOTO_DIR, state.photo_count),
+        _ => (VIDEO_DIR, state.video_count),
+    };
+
+    let mut data = HTTPResponseData {
+        media_type,
+        directories: None,
+        path: None,
+        start: None,
+        count: None,
+        list: None,
+    };
+
+    match path {
+        "/cgi-bin/media.cgi/api/v1/getdirectories" => data.directories = Some(dir.to_string()),
+        "/cgi-bin/media.cgi/api/v1/getmediacount" => {
+            data.path = Some(dir.to_string());
+            data.count = Some(count as i32);
+        }
+        "/cgi-bin/media.cgi/api/v1/getmedialist" => {
+            let start: u32 = params.get("start").and_then(|s| s.parse().ok()).unwrap_or(0);
+            let requested: u32 = params.get("count").and_then(|c| c.parse().ok()).unwrap_or(count);
+            let names: Vec<String> = (start + 1..=count)
+                .take(requested as usize)
+                .map(|index| media_name(media_type, index))
+                .collect();
+
+            data.path = Some(dir.to_string());
+            data.start = Some(start as i32);
+            data.count = Some(names.len() as i32);
+            data.list = Some(names.join(","));
+        }
+        _ => return media_file(path, state),
+    }
+
+    let response = HTTPResponse {
+        code: 200,
+        data,
+        success: true,
+        message: "success".to_string(),
+    };
+    let body = serde_json::to_vec(&response).expect("HTTPResponse serializes");
+    ("200 OK", "application/json", body)
+}
+
+/// Serves a synthetic photo or video if `path` names one that exists.
+fn media_file(path: &str, state: &EmulatorState) -> (&'static str, &'static str, Vec<u8>) {
+    let file = path
+        .strip_prefix("/photo/")
+        .and_then(|file| file.split_once('/'));
+
+    let media = match file {
+        Some((PHOTO_DIR, name)) => parse_index(name, "IMG_", ".jpg")
+            .filter(|index| (1..=state.photo_count).contains(index))
+            .map(|index| ("image/jpeg", synthetic_jpeg(index))),
+        Some((VIDEO_DIR, name)) => parse_index(name, "REC_", ".mp4")
+            .filter(|index| (1..=state.video_count).contains(index))
+            .map(|index| ("video/mp4", synthetic_mp4(index))),
+        _ => None,
+    };
+
+    match media {
+        Some((content_type, body)) => ("200 OK", content_type, body),
+        None => ("404 Not Found", "text/plain", b"not found".to_vec()),
+    }
+}
+
+/// File name of the `index`th photo or video.
+fn media_name(media_type: i32, index: u32) -> String {
+    match media_type {
+        0 => format!("IMG_{:0>4}.jpg", index),
+        _ => format!("REC_{:0>4}.mp4", index),
+    }
+}
+
+/// Parses the index out of a `{prefix}NNNN{suffix}` file name.
+fn parse_index(name: &str, prefix: &str, suffix: &str) -> Option<u32> {
+    name.strip_prefix(prefix)?.strip_suffix(suffix)?.parse().ok()
+}
+
+/// A minimal JPEG (SOI, a comment segment naming the photo, EOI).
+pub fn synthetic_jpeg(index: u32) -> Vec<u8> {
+    let comment = format!("A8 mini emulator IMG_{:0>4}", index);
+
+    let mut jpeg = vec![0xff, 0xd8, 0xff, 0xfe];
+    jpeg.extend_from_slice(&(comment.len() as u16 + 2).to_be_bytes());
+    jpeg.extend_from_slice(comment.as_bytes());
+    jpeg.extend_from_slice(&[0xff, 0xd9]);
+    jpeg
+}
+
+/// A minimal MP4 (an `ftyp` box followed by a `free` box naming the video).
+pub fn synthetic_mp4(index: u32) -> Vec<u8> {
+    let comment = format!("A8 mini emulator REC_{:0>4}", index);
+
+    let mut mp4 = Vec::new();
+    mp4.extend_from_slice(&20u32.to_be_bytes());
+    mp4.extend_from_slice(b"ftypisom");
+    mp4.extend_from_slice(&0x200u32.to_be_bytes());
+    mp4.extend_from_slice(b"isom");
+    mp4.extend_from_slice(&(comment.len() as u32 + 8).to_be_bytes());
+    mp4.extend_from_slice(b"free");
+    mp4.extend_from_slice(comment.as_bytes());
+    mp4
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_media_server_endpoints() -> Result<(), Box<dyn std::error::Error>> {
+        let state = Arc::new(Mutex::new(EmulatorState::default()));
+        state.lock().unwrap().photo_count = 2;
+
+        let server = MediaServer::bind("127.0.0.1:0", Arc::clone(&state)).await?;
+        let base_url = format!("http://{}", server.local_addr()?);
+        server.spawn();
+
+        let get_json = |query: &str| {
+            let url = format!("{}/cgi-bin/media.cgi/api/v1/{}", base_url, query);
+            async move { reqwest::get(url).await?.json::<HTTPResponse>().await }
+        };
+
+        let directories = get_json("getdirectories?media_type=1").await?;
+        assert_eq!(directories.data.directories.as_deref(), Some(VIDEO_DIR));
+
+        let count = get_json("getmediacount?media_type=0&path=101SIYI_IMG").await?;
+        assert!(count.success);
+        assert_eq!(count.data.count, Some(2));
+
+        let list = get_json("getmedialist?media_type=0&path=101SIYI_IMG&start=0&count=10").await?;
+        assert_eq!(list.data.list.as_deref(), Some("IMG_0001.jpg,IMG_0002.jpg"));
+
+        let photo = reqwest::get(format!("{}/photo/101SIYI_IMG/IMG_0002.jpg", base_url)).await?;
+        assert_eq!(photo.bytes().await?.to_vec(), synthetic_jpeg(2));
+
+        let missing = reqwest::get(format!("{}/photo/101SIYI_IMG/IMG_0003.jpg", base_url)).await?;
+        assert_eq!(missing.status(), reqwest::StatusCode::NOT_FOUND);
+        Ok(())
+    }
+}
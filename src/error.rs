@@ -0,0 +1,204 @@
+use std::{fmt, io};
+
+use crate::control::{CodecSpec, GimbalMode, RecordingState};
+use crate::frame::FrameError;
+
+/// Errors returned by the `A8Mini` API.
+#[derive(Debug)]
+pub enum A8MiniError {
+    /// No reply arrived within the receive timeout.
+    Timeout,
+    /// A reply failed its CRC16 check.
+    CrcMismatch { expected: u16, computed: u16 },
+    /// A reply answers a different command than the one sent.
+    UnexpectedCmdId { expected: u8, actual: u8 },
+    /// A reply's DATA field does not have the layout expected for its CMD_ID.
+    MalformedPayload { cmd_id: u8, expected: usize, actual: usize },
+    /// A reply is not a well-formed SIYI frame.
+    MalformedFrame(FrameError),
+    /// There is no live link to the camera: it was torn down, is being reconnected, or its reader task stopped
+    /// before a reply arrived.
+    Disconnected,
+    /// A local socket could not be bound.
+    Bind { addr: String, source: io::Error },
+    /// Any other socket error.
+    Io(io::Error),
+    /// The camera's HTTP server answered with a non-success status.
+    HttpStatus { url: String, status: reqwest::StatusCode },
+    /// An HTTP request could not be completed.
+    Http(reqwest::Error),
+    /// An HTTP response body is not the expected JSON.
+    Json(serde_json::Error),
+    /// Connection settings could not be parsed or are invalid.
+    InvalidConfig(String),
+    /// The camera acknowledged a command with a failure status.
+    Rejected { cmd_id: u8 },
+    /// A zoom multiple outside the range supported by the camera was requested.
+    ZoomOutOfRange { requested: f32, max: f32 },
+    /// The camera acknowledged a zoom to `target` times but still reported `reached` times when the wait ended.
+    ZoomNotReached { target: f32, reached: f32 },
+    /// The camera reported that taking a photo failed, e.g. because no TF card is inserted.
+    PhotoFailed,
+    /// The camera did not start (`recording: true`) or stop recording as requested and is left in `state`.
+    RecordingUnchanged { recording: bool, state: RecordingState },
+    /// The camera did not switch to the `requested` gimbal mode and is left in `mode`, `None` if it reports an
+    /// unknown one.
+    GimbalModeUnchanged { requested: GimbalMode, mode: Option<GimbalMode> },
+    /// A codec combination the camera does not support was requested.
+    UnsupportedCodec(String),
+    /// The camera acknowledged codec settings but reports different ones as applied.
+    CodecMismatch { requested: CodecSpec, applied: CodecSpec },
+}
+
+impl A8MiniError {
+    /// Whether the error is a timeout, i.e. the request may succeed if retried.
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, A8MiniError::Timeout)
+    }
+
+    /// Whether the camera answered with something that violates the protocol. Such replies are not retried.
+    ///
+    /// Only replies whose STX and header are intact can be attributed to a request; anything else is discarded
+    /// and shows up as `Timeout`.
+    pub fn is_protocol_error(&self) -> bool {
+        matches!(
+            self,
+            A8MiniError::CrcMismatch { .. }
+                | A8MiniError::UnexpectedCmdId { .. }
+                | A8MiniError::MalformedPayload { .. }
+                | A8MiniError::MalformedFrame(_)
+        )
+    }
+}
+
+impl fmt::Display for A8MiniError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            A8MiniError::Timeout => write!(f, "timed out waiting for reply"),
+            A8MiniError::CrcMismatch { expected, computed } => write!(
+                f,
+                "CRC mismatch: frame carries {:#06x}, computed {:#06x}",
+                expected, computed
+            ),
+            A8MiniError::UnexpectedCmdId { expected, actual } => write!(
+                f,
+                "unexpected CMD_ID: expected {:#04x}, got {:#04x}",
+                expected, actual
+            ),
+            A8MiniError::MalformedPayload { cmd_id, expected, actual } => write!(
+                f,
+                "malformed payload for CMD_ID {:#04x}: expected {} bytes, got {}",
+                cmd_id, expected, actual
+            ),
+            A8MiniError::MalformedFrame(e) => write!(f, "malformed frame: {}", e),
+            A8MiniError::Disconnected => write!(f, "no live link to the camera"),
+            A8MiniError::Bind { addr, source } => write!(f, "failed to bind {}: {}", addr, source),
+            A8MiniError::Io(e) => write!(f, "socket error: {}", e),
+            A8MiniError::HttpStatus { url, status } => write!(f, "HTTP {} from {}", status, url),
+            A8MiniError::Http(e) => write!(f, "HTTP request failed: {}", e),
+            A8MiniError::Json(e) => write!(f, "invalid JSON response: {}", e),
+            A8MiniError::InvalidConfig(message) => write!(f, "invalid configuration: {}", message),
+            A8MiniError::Rejected { cmd_id } => write!(f, "camera rejected command {:#04x}", cmd_id),
+            A8MiniError::ZoomOutOfRange { requested, max } => write!(
+                f,
+                "zoom {}x out of range: camera supports 1.0x to {}x",
+                requested, max
+            ),
+            A8MiniError::ZoomNotReached { target, reached } => write!(
+                f,
+                "camera did not reach {}x zoom (zoom: {}x)",
+                target, reached
+            ),
+            A8MiniError::PhotoFailed => write!(f, "camera failed to take a photo"),
+            A8MiniError::RecordingUnchanged { recording, state } => write!(
+                f,
+                "camera did not {} recording (state: {:?})",
+                if *recording { "start" } else { "stop" },
+                state
+            ),
+            A8MiniError::GimbalModeUnchanged { requested, mode: Some(mode) } => write!(
+                f,
+                "camera did not switch to {:?} gimbal mode (mode: {:?})",
+                requested, mode
+            ),
+            A8MiniError::GimbalModeUnchanged { requested, mode: None } => write!(
+                f,
+                "camera did not switch to {:?} gimbal mode (mode: unknown)",
+                requested
+            ),
+            A8MiniError::UnsupportedCodec(reason) => write!(f, "unsupported codec: {}", reason),
+            A8MiniError::CodecMismatch { requested, applied } => write!(
+                f,
+                "camera applied {:?} instead of {:?}",
+                applied, requested
+            ),
+        }
+    }
+}
+
+impl std::error::Error for A8MiniError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            A8MiniError::MalformedFrame(e) => Some(e),
+            A8MiniError::Bind { source, .. } => Some(source),
+            A8MiniError::Io(e) => Some(e),
+            A8MiniError::Http(e) => Some(e),
+            A8MiniError::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<FrameError> for A8MiniError {
+    fn from(e: FrameError) -> Self {
+        match e {
+            FrameError::CrcMismatch { expected, computed } => {
+                A8MiniError::CrcMismatch { expected, computed }
+            }
+            FrameError::UnexpectedCmdId { expected, actual } => {
+                A8MiniError::UnexpectedCmdId { expected, actual }
+            }
+            FrameError::MalformedPayload { cmd_id, expected, actual } => {
+                A8MiniError::MalformedPayload { cmd_id, expected, actual }
+            }
+            e => A8MiniError::MalformedFrame(e),
+        }
+    }
+}
+
+impl From<io::Error> for A8MiniError {
+    fn from(e: io::Error) -> Self {
+        A8MiniError::Io(e)
+    }
+}
+
+impl From<reqwest::Error> for A8MiniError {
+    fn from(e: reqwest::Error) -> Self {
+        A8MiniError::Http(e)
+    }
+}
+
+impl From<serde_json::Error> for A8MiniError {
+    fn from(e: serde_json::Error) -> Self {
+        A8MiniError::Json(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_error_classification() {
+        let crc: A8MiniError = FrameError::CrcMismatch { expected: 1, computed: 2 }.into();
+        assert!(matches!(crc, A8MiniError::CrcMismatch { expected: 1, computed: 2 }));
+        assert!(crc.is_protocol_error());
+
+        let truncated: A8MiniError = FrameError::Truncated { expected: 10, actual: 4 }.into();
+        assert!(matches!(truncated, A8MiniError::MalformedFrame(FrameError::Truncated { .. })));
+        assert!(truncated.is_protocol_error());
+
+        assert!(A8MiniError::Timeout.is_timeout());
+        assert!(!A8MiniError::Timeout.is_protocol_error());
+    }
+}
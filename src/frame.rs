@@ -0,0 +1,233 @@
+use std::fmt;
+
+use crate::{checksum, constants};
+
+/// Errors produced while decoding a SIYI frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameError {
+    /// The first two bytes are not the `0x55 0x66` starting mark.
+    InvalidStx([u8; 2]),
+    /// The buffer ends before the frame declared by its header does.
+    Truncated { expected: usize, actual: usize },
+    /// The buffer holds more bytes than the frame declared by its header.
+    Oversized { expected: usize, actual: usize },
+    /// The trailing CRC16 does not match the one computed over the frame.
+    CrcMismatch { expected: u16, computed: u16 },
+    /// The frame is valid but answers a different command than expected.
+    UnexpectedCmdId { expected: u8, actual: u8 },
+    /// The DATA field does not have the layout expected for its CMD_ID.
+    MalformedPayload { cmd_id: u8, expected: usize, actual: usize },
+    /// A field of the DATA field holds a value outside the range defined for it.
+    InvalidField { cmd_id: u8, field: &'static str, value: u8 },
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            FrameError::InvalidStx(stx) => write!(f, "invalid STX {:02x?}", stx),
+            FrameError::Truncated { expected, actual } => write!(
+                f,
+                "truncated frame: expected {} bytes, got {}",
+                expected, actual
+            ),
+            FrameError::Oversized { expected, actual } => write!(
+                f,
+                "oversized frame: expected {} bytes, got {}",
+                expected, actual
+            ),
+            FrameError::CrcMismatch { expected, computed } => write!(
+                f,
+                "CRC mismatch: frame carries {:#06x}, computed {:#06x}",
+                expected, computed
+            ),
+            FrameError::UnexpectedCmdId { expected, actual } => write!(
+                f,
+                "unexpected CMD_ID: expected {:#04x}, got {:#04x}",
+                expected, actual
+            ),
+            FrameError::MalformedPayload { cmd_id, expected, actual } => write!(
+                f,
+                "malformed payload for CMD_ID {:#04x}: expected {} bytes, got {}",
+                cmd_id, expected, actual
+            ),
+            FrameError::InvalidField { cmd_id, field, value } => write!(
+                f,
+                "invalid {} {} in payload for CMD_ID {:#04x}",
+                field, value, cmd_id
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+/// A single SIYI SDK frame. See the protocol table in `constants` for the wire layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub ctrl: u8,
+    pub seq: u16,
+    pub cmd_id: u8,
+    pub data: Vec<u8>,
+}
+
+impl Frame {
+    /// Creates a new frame with the default `CTRL` and `SEQ` of 0.
+    pub fn new(cmd_id: u8, data: Vec<u8>) -> Self {
+        Frame {
+            ctrl: constants::CTRL_DEFAULT,
+            seq: 0,
+            cmd_id,
+            data,
+        }
+    }
+
+    /// Returns the frame with its `SEQ` set to `seq`.
+    pub fn with_seq(mut self, seq: u16) -> Self {
+        self.seq = seq;
+        self
+    }
+
+    /// Total encoded length of the frame in bytes, including header and CRC16.
+    pub fn encoded_len(&self) -> usize {
+        constants::FRAME_HEADER_LEN + self.data.len() + constants::FRAME_CRC_LEN
+    }
+
+    /// Encodes the frame into bytes, appending the CRC16.
+    ///
+    /// Panics if `data` is longer than `u16::MAX` bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let data_len = u16::try_from(self.data.len()).expect("DATA does not fit in DATALEN");
+
+        let mut byte_arr: Vec<u8> = Vec::with_capacity(self.encoded_len());
+        byte_arr.extend_from_slice(&constants::STX);
+        byte_arr.push(self.ctrl);
+        byte_arr.extend_from_slice(&data_len.to_le_bytes());
+        byte_arr.extend_from_slice(&self.seq.to_le_bytes());
+        byte_arr.push(self.cmd_id);
+        byte_arr.extend_from_slice(&self.data);
+
+        byte_arr.extend_from_slice(&checksum::crc16_calc(&byte_arr, 0));
+
+        byte_arr
+    }
+
+    /// Reads CMD_ID and SEQ from a datagram whose STX and header are intact, even if the rest of it is not.
+    pub(crate) fn peek_header(bytes: &[u8]) -> Option<(u8, u16)> {
+        if bytes.len() < constants::FRAME_HEADER_LEN || bytes[..2] != constants::STX {
+            return None;
+        }
+
+        Some((bytes[7], u16::from_le_bytes([bytes[5], bytes[6]])))
+    }
+
+    /// Decodes a frame from exactly one datagram, validating STX, DATALEN and CRC16.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FrameError> {
+        if bytes.len() < constants::FRAME_HEADER_LEN + constants::FRAME_CRC_LEN {
+            return Err(FrameError::Truncated {
+                expected: constants::FRAME_HEADER_LEN + constants::FRAME_CRC_LEN,
+                actual: bytes.len(),
+            });
+        }
+
+        if bytes[..2] != constants::STX {
+            return Err(FrameError::InvalidStx([bytes[0], bytes[1]]));
+        }
+
+        let data_len = u16::from_le_bytes([bytes[3], bytes[4]]) as usize;
+        let frame_len = constants::FRAME_HEADER_LEN + data_len + constants::FRAME_CRC_LEN;
+        if bytes.len() < frame_len {
+            return Err(FrameError::Truncated {
+                expected: frame_len,
+                actual: bytes.len(),
+            });
+        }
+        if bytes.len() > frame_len {
+            return Err(FrameError::Oversized {
+                expected: frame_len,
+                actual: bytes.len(),
+            });
+        }
+
+        let crc_start = frame_len - constants::FRAME_CRC_LEN;
+        let expected = u16::from_le_bytes([bytes[crc_start], bytes[crc_start + 1]]);
+        let computed = u16::from_le_bytes(checksum::crc16_calc(&bytes[..crc_start], 0));
+        if expected != computed {
+            return Err(FrameError::CrcMismatch { expected, computed });
+        }
+
+        Ok(Frame {
+            ctrl: bytes[2],
+            seq: u16::from_le_bytes([bytes[5], bytes[6]]),
+            cmd_id: bytes[7],
+            data: bytes[constants::FRAME_HEADER_LEN..crc_start].to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_round_trip() {
+        let frame = Frame::new(0x0e, vec![0x82, 0x00, 0xec, 0xff]).with_seq(0x1234);
+        let bytes = frame.to_bytes();
+
+        assert_eq!(bytes.len(), frame.encoded_len());
+        assert_eq!(&bytes[..8], &[0x55, 0x66, 0x01, 0x04, 0x00, 0x34, 0x12, 0x0e]);
+        assert_eq!(Frame::from_bytes(&bytes), Ok(frame));
+    }
+
+    #[test]
+    fn test_frame_decode_hardcoded_commands() {
+        for cmd in constants::HARDCODED_COMMANDS {
+            let frame = Frame::from_bytes(cmd).unwrap();
+            assert_eq!(frame.to_bytes(), cmd);
+        }
+    }
+
+    #[test]
+    fn test_frame_peek_header() {
+        let mut bytes = Frame::new(0x0d, vec![0x01, 0x02]).with_seq(0x0102).to_bytes();
+        bytes[9] ^= 0xff;
+
+        assert_eq!(Frame::peek_header(&bytes), Some((0x0d, 0x0102)));
+        assert_eq!(Frame::peek_header(&bytes[..7]), None);
+        assert_eq!(Frame::peek_header(&[0x66; 12]), None);
+    }
+
+    #[test]
+    fn test_frame_decode_errors() {
+        let bytes = Frame::new(0x0d, vec![0x01, 0x02]).to_bytes();
+
+        assert_eq!(
+            Frame::from_bytes(&bytes[..4]),
+            Err(FrameError::Truncated { expected: 10, actual: 4 })
+        );
+        assert_eq!(
+            Frame::from_bytes(&bytes[..11]),
+            Err(FrameError::Truncated { expected: 12, actual: 11 })
+        );
+
+        let mut oversized = bytes.clone();
+        oversized.push(0x00);
+        assert_eq!(
+            Frame::from_bytes(&oversized),
+            Err(FrameError::Oversized { expected: 12, actual: 13 })
+        );
+
+        let mut bad_stx = bytes.clone();
+        bad_stx[1] = 0x67;
+        assert_eq!(
+            Frame::from_bytes(&bad_stx),
+            Err(FrameError::InvalidStx([0x55, 0x67]))
+        );
+
+        let mut corrupted = bytes.clone();
+        corrupted[8] ^= 0xff;
+        assert!(matches!(
+            Frame::from_bytes(&corrupted),
+            Err(FrameError::CrcMismatch { .. })
+        ));
+    }
+}
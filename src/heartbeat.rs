@@ -0,0 +1,108 @@
+use std::fmt;
+use std::sync::Weak;
+
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Instant, MissedTickBehavior};
+use tracing::{debug, info, warn};
+
+use crate::config::HeartbeatOptions;
+use crate::control::{A8MiniSimpleCommand, Command};
+use crate::Inner;
+
+/// Health of the link to the camera, judged by how long ago it last sent a frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LinkStatus {
+    /// The camera answered within `HeartbeatOptions::degraded_after`.
+    Connected,
+    /// The camera has been silent for longer than `HeartbeatOptions::degraded_after`.
+    Degraded,
+    /// The camera has been silent for longer than `HeartbeatOptions::lost_after`, or never answered.
+    Lost,
+}
+
+impl LinkStatus {
+    /// Judges the link given when the camera last sent a frame.
+    pub(crate) fn after_silence(last_received: Option<Instant>, options: &HeartbeatOptions) -> Self {
+        match last_received.map(|last_received| last_received.elapsed()) {
+            Some(silence) if silence <= options.degraded_after => LinkStatus::Connected,
+            Some(silence) if silence <= options.lost_after => LinkStatus::Degraded,
+            _ => LinkStatus::Lost,
+        }
+    }
+}
+
+impl fmt::Display for LinkStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkStatus::Connected => write!(f, "connected"),
+            LinkStatus::Degraded => write!(f, "degraded"),
+            LinkStatus::Lost => write!(f, "lost"),
+        }
+    }
+}
+
+/// Spawns the task sending heartbeats for `inner` and publishing its `LinkStatus`. The task exits once `inner`
+/// is dropped.
+pub(crate) fn spawn(inner: Weak<Inner>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let Some(options) = inner.upgrade().map(|inner| inner.config.heartbeat) else {
+            return;
+        };
+        let heartbeat = A8MiniSimpleCommand::Heartbeat.to_frame();
+        let probe = A8MiniSimpleCommand::AttitudeInformation.to_frame();
+
+        let mut ticks = interval(options.interval);
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticks.tick().await;
+            let Some(inner) = inner.upgrade() else {
+                return;
+            };
+
+            if let Err(e) = inner.send_frame(&heartbeat.clone().with_seq(inner.next_seq())).await {
+                warn!(error = %e, "failed to send heartbeat");
+            }
+
+            // Heartbeats are not acknowledged, so probe with a request the camera always answers
+            if let Err(e) = inner.request(probe.clone()).await {
+                debug!(error = %e, "heartbeat probe failed");
+            }
+
+            let status = LinkStatus::after_silence(inner.dispatcher.last_received(), &options);
+            inner.link_status.send_if_modified(|current| {
+                if *current == status {
+                    return false;
+                }
+
+                match status {
+                    LinkStatus::Connected => info!(from = %current, "link connected"),
+                    _ => warn!(from = %current, to = %status, "link status changed"),
+                }
+                *current = status;
+                true
+            });
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_link_status_after_silence() {
+        let options = HeartbeatOptions {
+            degraded_after: Duration::from_secs(3),
+            lost_after: Duration::from_secs(10),
+            ..HeartbeatOptions::default()
+        };
+        let silent_for = |secs| Some(Instant::now() - Duration::from_secs(secs));
+
+        assert_eq!(LinkStatus::after_silence(None, &options), LinkStatus::Lost);
+        assert_eq!(LinkStatus::after_silence(silent_for(0), &options), LinkStatus::Connected);
+        assert_eq!(LinkStatus::after_silence(silent_for(5), &options), LinkStatus::Degraded);
+        assert_eq!(LinkStatus::after_silence(silent_for(11), &options), LinkStatus::Lost);
+    }
+}
@@ -1,175 +1,1581 @@
 #![allow(non_snake_case)]
 
-use bincode::deserialize;
-use std::error::Error;
-use tokio::{net::UdpSocket, time::timeout};
+use chrono::{DateTime, Utc};
+use config::A8MiniConfig;
+use control::{Command, Response};
+use std::io;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU16, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::{
+    net::UdpSocket,
+    sync::{broadcast, watch, Notify},
+    task::JoinHandle,
+    time::{sleep, timeout, Instant},
+};
+use tracing::{debug, instrument, trace, warn};
 
 pub mod checksum;
+mod clock;
+pub mod config;
 pub mod constants;
 pub mod control;
+mod dispatcher;
+pub mod emulator;
+pub mod error;
+pub mod frame;
+pub mod heartbeat;
+pub mod media;
+pub mod reconnect;
 
-#[derive(Debug)]
-/// Represents the A8Mini camera API with a dedicate UDP socket for both `Command`s and `HTTPQuery`s.
+pub use error::A8MiniError;
+pub use heartbeat::LinkStatus;
+pub use reconnect::{LossReason, ReconnectEvent};
+
+#[derive(Debug, Clone)]
+/// Represents the A8Mini camera API with a dedicated UDP socket for `Command`s and a `media::MediaClient` for
+/// `HTTPQuery`s.
+///
+/// A background task owns reception on the command socket and hands each reply to the request waiting on it,
+/// so clones of an `A8Mini` can be shared across tasks and issue commands concurrently.
 pub struct A8Mini {
-    pub command_socket: UdpSocket,
-    pub http_socket: UdpSocket,
+    inner: Arc<Inner>,
+    media: media::MediaClient,
+}
+
+#[derive(Debug)]
+struct Inner {
+    config: A8MiniConfig,
+    link: Mutex<Option<Link>>,
+    next_seq: AtomicU16,
+    dispatcher: Arc<dispatcher::Dispatcher>,
+    heartbeat: Mutex<Option<JoinHandle<()>>>,
+    link_status: watch::Sender<LinkStatus>,
+    supervisor: Mutex<Option<JoinHandle<()>>>,
+    consecutive_failures: AtomicU32,
+    link_lost: Arc<Notify>,
+    replay: Mutex<reconnect::Replay>,
+    reconnect_events: broadcast::Sender<ReconnectEvent>,
+    clock_sync: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        let tasks = [
+            self.heartbeat.get_mut().unwrap().take(),
+            self.supervisor.get_mut().unwrap().take(),
+            self.clock_sync.get_mut().unwrap().take(),
+        ];
+        for task in tasks.into_iter().flatten() {
+            task.abort();
+        }
+    }
+}
+
+/// The command socket and the task reading from it.
+#[derive(Debug)]
+struct Link {
+    socket: Arc<UdpSocket>,
+    reader: JoinHandle<()>,
+}
+
+impl Link {
+    /// Binds and connects the command socket described by `config`, dispatching its replies to `dispatcher`.
+    async fn establish(config: &A8MiniConfig, dispatcher: &Arc<dispatcher::Dispatcher>) -> Result<Link, A8MiniError> {
+        let socket = bind(SocketAddr::new(config.local_bind_addr, config.local_command_port)).await?;
+        socket.connect(config.camera_command_addr()).await?;
+
+        let socket = Arc::new(socket);
+        let reader = dispatcher.spawn_reader(Arc::clone(&socket));
+        Ok(Link { socket, reader })
+    }
+
+    /// Stops the reader task and waits until it has released its handle on the socket.
+    async fn close(mut self) {
+        self.reader.abort();
+        let _ = (&mut self.reader).await;
+    }
+}
+
+impl Drop for Link {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+impl Inner {
+    /// Returns the next outbound SEQ. SEQ 0 is skipped on wrap-around so it never matches a reply from
+    /// firmware that does not echo SEQ.
+    fn next_seq(&self) -> u16 {
+        loop {
+            let seq = self.next_seq.fetch_add(1, Ordering::Relaxed);
+            if seq != 0 {
+                return seq;
+            }
+        }
+    }
+
+    /// Sends `frame` as is. Callers stamp it with `next_seq` first.
+    async fn send_frame(&self, frame: &frame::Frame) -> Result<(), A8MiniError> {
+        let bytes = frame.to_bytes();
+        trace!(bytes = ?format_args!("{:02x?}", bytes), "sending frame");
+
+        let socket = match &*self.link.lock().unwrap() {
+            Some(link) => Arc::clone(&link.socket),
+            None => return Err(A8MiniError::Disconnected),
+        };
+        let send_len = socket.send(&bytes).await?;
+
+        if send_len == 0 {
+            warn!("no bytes sent");
+            return Err(io::Error::new(io::ErrorKind::WriteZero, "no bytes sent").into());
+        }
+
+        debug!(cmd_id = frame.cmd_id, seq = frame.seq, len = send_len, "sent frame");
+        self.replay.lock().unwrap().record(frame);
+
+        Ok(())
+    }
+
+    /// Stamps `frame` with the next SEQ, sends it and waits for its reply.
+    async fn request(&self, frame: frame::Frame) -> Result<frame::Frame, A8MiniError> {
+        let request = frame.with_seq(self.next_seq());
+        let reply_rx = self.dispatcher.register(&request);
+
+        if let Err(e) = self.send_frame(&request).await {
+            self.dispatcher.unregister(&request);
+            self.record_failure();
+            return Err(e);
+        }
+
+        let sent_at = Instant::now();
+        match timeout(self.config.recv_timeout, reply_rx).await {
+            Ok(Ok(Ok(reply))) => {
+                debug!(
+                    cmd_id = reply.cmd_id,
+                    seq = reply.seq,
+                    len = reply.encoded_len(),
+                    latency_ms = sent_at.elapsed().as_secs_f64() * 1000.0,
+                    "received reply"
+                );
+                self.consecutive_failures.store(0, Ordering::Relaxed);
+                Ok(reply)
+            }
+            Ok(Ok(Err(e))) => {
+                warn!(cmd_id = request.cmd_id, seq = request.seq, error = %e, "received corrupted reply");
+                Err(e.into())
+            }
+            Ok(Err(_)) => Err(A8MiniError::Disconnected),
+            Err(_) => {
+                self.dispatcher.unregister(&request);
+                warn!(cmd_id = request.cmd_id, seq = request.seq, "timed out waiting for reply");
+                self.record_failure();
+                Err(A8MiniError::Timeout)
+            }
+        }
+    }
+
+    /// Counts a failed request, waking the reconnect supervisor once the consecutive failure limit is reached.
+    fn record_failure(&self) {
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if failures == self.config.reconnect.max_consecutive_failures {
+            self.link_lost.notify_one();
+        }
+    }
+
+    /// Replaces the link with a freshly bound one and checks that the camera answers on it.
+    async fn reestablish(&self) -> Result<(), A8MiniError> {
+        // Close the old socket first so a fixed local port can be bound again
+        let old_link = self.link.lock().unwrap().take();
+        if let Some(old_link) = old_link {
+            old_link.close().await;
+        }
+        let link = Link::establish(&self.config, &self.dispatcher).await?;
+        *self.link.lock().unwrap() = Some(link);
+
+        self.request(control::A8MiniSimpleCommand::AttitudeInformation.to_frame()).await?;
+        self.consecutive_failures.store(0, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Sets the camera's clock to the host's current UTC time and returns the time sent.
+    async fn sync_clock(&self) -> Result<DateTime<Utc>, A8MiniError> {
+        let now = Utc::now();
+        // Times before the Unix epoch cannot be represented on the wire
+        let timestamp = now.timestamp_micros().max(0) as u64;
+
+        let ack_frame = self.request(control::A8MiniComplexCommand::SetTimeUTC(timestamp).to_frame()).await?;
+        if !control::A8MiniTimeAck::from_frame(&ack_frame)?.accepted {
+            return Err(A8MiniError::Rejected { cmd_id: ack_frame.cmd_id });
+        }
+
+        Ok(now)
+    }
+
+    /// Broadcasts `event` to reconnect event subscribers, if any.
+    fn emit(&self, event: ReconnectEvent) {
+        let _ = self.reconnect_events.send(event);
+    }
 }
 
 impl A8Mini {
     /// Connect to and creates a new `A8Mini` using default ip address `192.168.144.25` and default port 37260 and port 82. 
-    /// Remote ports are mapped to port 8080 and port 8088 on local.
-    pub async fn connect() -> Result<Self, Box<dyn Error>> {
-        Ok(Self::connect_to(
-            constants::CAMERA_IP,
-            constants::CAMERA_COMMAND_PORT,
-            constants::CAMERA_HTTP_PORT,
-            "8080",
-            "8088",
-        )
-        .await?)
-    }
-
-    /// Repeatedly tries to reconnect a total of `max_iter`` times
+    /// Local sockets are bound to ephemeral ports; use `connect_with` to choose them.
+    pub async fn connect() -> Result<Self, A8MiniError> {
+        Self::connect_with(A8MiniConfig::default()).await
+    }
+
+    /// Repeatedly tries to connect a total of `max_iter` times, waiting out the default `config::ReconnectPolicy`
+    /// backoff in between. An attempt only succeeds once the camera answers an attitude request.
     pub async fn connect_yapping(
         max_iter: i32,
-    ) -> Result<A8Mini, Box<dyn Error>> {
-        for _ in 1..max_iter {
-            let connect_attempt = Self::connect().await;
-            if connect_attempt.is_ok() {
-                return Ok(connect_attempt.unwrap());
+    ) -> Result<A8Mini, A8MiniError> {
+        let policy = A8MiniConfig::default().reconnect;
+        let max_iter = max_iter.max(0) as u32;
+
+        let mut last_error = A8MiniError::Timeout;
+        for attempt in 1..=max_iter {
+            match Self::connect().await {
+                Ok(camera) => match camera.get_attitude_information().await {
+                    Ok(_) => return Ok(camera),
+                    Err(e) => last_error = e,
+                },
+                Err(e) => last_error = e,
+            }
+
+            if attempt < max_iter {
+                sleep(policy.backoff(attempt)).await;
             }
         }
 
-        Err("max_iter reached".into())
+        Err(last_error)
     }
 
-    /// Connects to and creates a new `A8Mini` given network args.
+    /// Connects to and creates a new `A8Mini` given network args. `HTTPQuery`s are sent to `camera_ip` on
+    /// `camera_http_port`. `_local_http_port` is unused since HTTP queries no longer need a local UDP socket.
     pub async fn connect_to(
         camera_ip: &str,
         camera_command_port: &str,
         camera_http_port: &str,
         local_command_port: &str,
-        local_http_port: &str,
-    ) -> Result<A8Mini, Box<dyn Error>> {
-        let camera: A8Mini = A8Mini {
-            command_socket: UdpSocket::bind(format!("0.0.0.0:{}", local_command_port)).await?,
-            http_socket: UdpSocket::bind(format!("0.0.0.0:{}", local_http_port)).await?,
+        _local_http_port: &str,
+    ) -> Result<A8Mini, A8MiniError> {
+        let config = A8MiniConfig::default()
+            .with_camera_ip(camera_ip)
+            .with_camera_command_port(parse_port(camera_command_port)?)
+            .with_camera_http_port(parse_port(camera_http_port)?)
+            .with_local_command_port(parse_port(local_command_port)?);
+
+        Self::connect_with(config).await
+    }
+
+    /// Connects to and creates a new `A8Mini` given an `A8MiniConfig`.
+    pub async fn connect_with(config: A8MiniConfig) -> Result<A8Mini, A8MiniError> {
+        config.validate()?;
+        let dispatcher = Arc::new(dispatcher::Dispatcher::default());
+        let link = Link::establish(&config, &dispatcher).await?;
+
+        let media = media::MediaClient::new(&config.http_base_url(), &config.http)?;
+        let (heartbeat_enabled, reconnect_enabled) = (config.heartbeat.enabled, config.reconnect.enabled);
+        let clock_sync_enabled = config.clock_sync.enabled;
+
+        let camera = A8Mini {
+            media,
+            inner: Arc::new(Inner {
+                config,
+                link: Mutex::new(Some(link)),
+                next_seq: AtomicU16::new(1),
+                dispatcher,
+                heartbeat: Mutex::new(None),
+                link_status: watch::Sender::new(LinkStatus::Lost),
+                supervisor: Mutex::new(None),
+                consecutive_failures: AtomicU32::new(0),
+                link_lost: Arc::new(Notify::new()),
+                replay: Mutex::new(reconnect::Replay::default()),
+                reconnect_events: broadcast::channel(16).0,
+                clock_sync: Mutex::new(None),
+            }),
         };
 
-        camera
-            .command_socket
-            .connect(format!("{}:{}", camera_ip, camera_command_port))
-            .await?;
-        camera
-            .http_socket
-            .connect(format!("{}:{}", camera_ip, camera_http_port))
-            .await?;
+        if heartbeat_enabled {
+            camera.start_heartbeat();
+        }
+        if reconnect_enabled {
+            camera.start_reconnect_supervisor();
+        }
+        if clock_sync_enabled {
+            camera.start_clock_sync();
+        }
+
         Ok(camera)
     }
 
-    /// Sends a `control::Command` blind. This should be used for all commands that don't have a ACK.
-    pub async fn send_command_blind<T: control::Command>(
-        &self,
-        command: T,
-    ) -> Result<(), Box<dyn Error>> {
-        println!(
-            "[COMMAND] Sending command with bytes: {:?}",
-            command.to_bytes()
-        );
-        println!(
-            "[COMMAND] Sending command with DATA_LEN: {:?} | CMD_ID: {:?}",
-            command.to_bytes()[3],
-            command.to_bytes()[7]
-        );
+    /// Settings this `A8Mini` was connected with.
+    pub fn config(&self) -> &A8MiniConfig {
+        &self.inner.config
+    }
 
-        let send_len = self.command_socket.send(command.to_bytes().as_slice()).await?;
+    /// Returns the `A8Mini` with `HTTPQuery`s rendered against `http_base_url` (e.g. `http://10.0.0.7:82`)
+    /// instead of the URL derived from the connection arguments.
+    pub fn with_http_base_url(mut self, http_base_url: &str) -> Self {
+        self.media = self.media.with_base_url(http_base_url);
+        self
+    }
 
-        if send_len == 0 {
-            println!("[COMMAND] No bytes sent.");
-            return Err("No bytes sent.".into());
+    /// Base URL `HTTPQuery`s are rendered against.
+    pub fn http_base_url(&self) -> &str {
+        self.media.base_url()
+    }
+
+    /// Client for the camera's HTTP media API.
+    pub fn media(&self) -> &media::MediaClient {
+        &self.media
+    }
+
+    /// Starts the background task sending heartbeats and probing the link every
+    /// `config::HeartbeatOptions::interval`. Does nothing if it is already running.
+    ///
+    /// Started automatically on connect when `config::HeartbeatOptions::enabled` is set.
+    pub fn start_heartbeat(&self) {
+        let mut heartbeat = self.inner.heartbeat.lock().unwrap();
+        if heartbeat.as_ref().is_some_and(|task| !task.is_finished()) {
+            return;
         }
 
-        println!("[COMMAND] Sent {} bytes successfully.", send_len);
+        *heartbeat = Some(heartbeat::spawn(Arc::downgrade(&self.inner)));
+    }
 
-        Ok(())
+    /// Stops the heartbeat task, if running. `watch_link_status` receivers keep the last reported status.
+    pub fn stop_heartbeat(&self) {
+        if let Some(heartbeat) = self.inner.heartbeat.lock().unwrap().take() {
+            heartbeat.abort();
+        }
     }
 
-    /// Sends a `control::Command` expecting an ACK. Returns received ACK response bytes.
-    pub async fn send_command<T: control::Command>(
+    /// Starts the background task re-establishing the link with exponential backoff once it is lost, according to
+    /// `config::ReconnectPolicy`. After reconnecting, the last requested gimbal mode and codec settings are
+    /// replayed. Does nothing if it is already running.
+    ///
+    /// Loss is detected through `config::ReconnectPolicy::max_consecutive_failures` and, while the heartbeat task
+    /// runs, through it reporting `LinkStatus::Lost`. Started automatically on connect when
+    /// `config::ReconnectPolicy::enabled` is set.
+    pub fn start_reconnect_supervisor(&self) {
+        let mut supervisor = self.inner.supervisor.lock().unwrap();
+        if supervisor.as_ref().is_some_and(|task| !task.is_finished()) {
+            return;
+        }
+
+        *supervisor = Some(reconnect::spawn(
+            Arc::downgrade(&self.inner),
+            self.inner.link_status.subscribe(),
+            Arc::clone(&self.inner.link_lost),
+        ));
+    }
+
+    /// Stops the reconnect supervisor, if running, including any reconnect in progress.
+    pub fn stop_reconnect_supervisor(&self) {
+        if let Some(supervisor) = self.inner.supervisor.lock().unwrap().take() {
+            supervisor.abort();
+        }
+    }
+
+    /// Subscribes to the function feedback the camera pushes from now on, such as photo and recording results.
+    /// Events survive reconnects. Slow subscribers miss the oldest events, see `broadcast::Receiver::recv`.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<control::CameraEvent> {
+        self.inner.dispatcher.subscribe_events()
+    }
+
+    /// Subscribes to the reconnect supervisor's `ReconnectEvent`s.
+    pub fn subscribe_reconnect_events(&self) -> broadcast::Receiver<ReconnectEvent> {
+        self.inner.reconnect_events.subscribe()
+    }
+
+    /// Starts the background task calling `sync_clock` right away and then every
+    /// `config::ClockSyncOptions::interval`. Does nothing if it is already running.
+    ///
+    /// Started automatically on connect when `config::ClockSyncOptions::enabled` is set.
+    pub fn start_clock_sync(&self) {
+        let mut clock_sync = self.inner.clock_sync.lock().unwrap();
+        if clock_sync.as_ref().is_some_and(|task| !task.is_finished()) {
+            return;
+        }
+
+        *clock_sync = Some(clock::spawn(Arc::downgrade(&self.inner)));
+    }
+
+    /// Stops the clock sync task, if running.
+    pub fn stop_clock_sync(&self) {
+        if let Some(clock_sync) = self.inner.clock_sync.lock().unwrap().take() {
+            clock_sync.abort();
+        }
+    }
+
+    /// Sets the camera's clock to the host's current UTC time, so media timestamps line up with host-side logs.
+    /// Returns the time sent.
+    ///
+    /// Returns `A8MiniError::Rejected` if the camera does not accept the time.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn sync_clock(&self) -> Result<DateTime<Utc>, A8MiniError> {
+        self.inner.sync_clock().await
+    }
+
+    /// Health of the link judged by how long ago the camera last sent a frame. Without the heartbeat task running,
+    /// this only reflects replies to commands sent by the caller.
+    pub fn link_status(&self) -> LinkStatus {
+        LinkStatus::after_silence(self.inner.dispatcher.last_received(), &self.inner.config.heartbeat)
+    }
+
+    /// Subscribes to the link status reported by the heartbeat task. Starts out as `LinkStatus::Lost` until the
+    /// first heartbeat round has been answered.
+    pub fn watch_link_status(&self) -> watch::Receiver<LinkStatus> {
+        self.inner.link_status.subscribe()
+    }
+
+    /// Sends a `control::Command` blind. This should be used for all commands that don't have a ACK.
+    #[instrument(level = "debug", skip_all, fields(command = ?command))]
+    pub async fn send_command_blind<T: control::Command>(
         &self,
         command: T,
-    ) -> Result<[u8; constants::RECV_BUFF_SIZE], Box<dyn Error>> {
-        self.send_command_blind(command).await?;
-        let mut recv_buffer = [0; constants::RECV_BUFF_SIZE];
+    ) -> Result<(), A8MiniError> {
+        let request = command.to_frame().with_seq(self.inner.next_seq());
+        self.inner.send_frame(&request).await
+    }
 
-        println!("[COMMAND] Waiting for response.");
+    /// Sends a `control::Command` expecting an ACK. Returns the decoded ACK `frame::Frame`.
+    ///
+    /// The reply is matched to this request by CMD_ID (and SEQ, when the firmware echoes it), so replies to
+    /// other requests, including ones that arrive late, are never returned here. Timed out requests are resent
+    /// with a new SEQ according to the configured `config::RetryPolicy`.
+    #[instrument(level = "debug", skip_all, fields(command = ?command))]
+    pub async fn send_command<T: control::Command>(
+        &self,
+        command: T,
+    ) -> Result<frame::Frame, A8MiniError> {
+        let frame = command.to_frame();
+        let retry = self.inner.config.retry;
 
-        let recv_len = timeout(
-            constants::RECV_TIMEOUT,
-            self.command_socket.recv(&mut recv_buffer),
-        )
-        .await??;
-        if recv_len == 0 {
-            println!("[COMMAND] No bytes received.");
-            return Err("No bytes received.".into());
+        let mut attempt = 1;
+        loop {
+            match self.inner.request(frame.clone()).await {
+                Err(A8MiniError::Timeout) if attempt < retry.max_attempts => {
+                    debug!(attempt, "retrying after timeout");
+                    attempt += 1;
+                    sleep(retry.delay).await;
+                }
+                result => return result,
+            }
         }
-
-        println!(
-            "[COMMAND] Response of size {} received successfully: {:?}",
-            recv_len, recv_buffer
-        );
-        Ok(recv_buffer)
     }
 
     /// Retrieves attitude information from the camera. 
     /// Can be used as a system connectivity check.
     pub async fn get_attitude_information(
         &self,
-    ) -> Result<control::A8MiniAtittude, Box<dyn Error>> {
-        let attitude_bytes = self
+    ) -> Result<control::A8MiniAtittude, A8MiniError> {
+        let attitude_frame = self
             .send_command(control::A8MiniSimpleCommand::AttitudeInformation)
             .await?;
-        let attitude_info: control::A8MiniAtittude = deserialize(&attitude_bytes)?;
+        let attitude_info = control::A8MiniAtittude::from_frame(&attitude_frame)?;
         Ok(attitude_info)
     }
 
+    /// Retrieves the maximum zoom multiple supported by the camera.
+    pub async fn get_max_zoom(&self) -> Result<f32, A8MiniError> {
+        let max_zoom_frame = self
+            .send_command(control::A8MiniSimpleCommand::MaxZoomInformation)
+            .await?;
+        Ok(control::A8MiniMaxZoom::from_frame(&max_zoom_frame)?.zoom)
+    }
+
+    /// Retrieves the current zoom multiple.
+    pub async fn get_current_zoom(&self) -> Result<f32, A8MiniError> {
+        let zoom_frame = self
+            .send_command(control::A8MiniSimpleCommand::CurrentZoomInformation)
+            .await?;
+        Ok(control::A8MiniCurrentZoom::from_frame(&zoom_frame)?.zoom)
+    }
+
+    /// Zooms in one step. Returns the zoom multiple reached.
+    pub async fn zoom_in(&self) -> Result<f32, A8MiniError> {
+        let zoom_frame = self.send_command(control::A8MiniSimpleCommand::ZoomIn).await?;
+        Ok(control::A8MiniManualZoom::from_frame(&zoom_frame)?.zoom)
+    }
+
+    /// Zooms out one step. Returns the zoom multiple reached.
+    pub async fn zoom_out(&self) -> Result<f32, A8MiniError> {
+        let zoom_frame = self.send_command(control::A8MiniSimpleCommand::ZoomOut).await?;
+        Ok(control::A8MiniManualZoom::from_frame(&zoom_frame)?.zoom)
+    }
+
+    /// Zooms to `zoom` times, rounded to a tenth, after checking it against the camera's maximum zoom.
+    ///
+    /// Once acknowledged, polls the current zoom until it reaches the target and returns it. Returns
+    /// `A8MiniError::ZoomNotReached` with the zoom last reported if `constants::STATE_CONFIRM_TIMEOUT` elapses first.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn zoom_to(&self, zoom: f32) -> Result<f32, A8MiniError> {
+        let max_zoom = self.get_max_zoom().await?;
+        let zoom_tenths = (zoom * 10.0).round();
+        if !(10.0..=max_zoom * 10.0).contains(&zoom_tenths) {
+            return Err(A8MiniError::ZoomOutOfRange { requested: zoom, max: max_zoom });
+        }
+
+        let command = control::A8MiniComplexCommand::SetAbsoluteZoom(zoom);
+        let ack_frame = self.send_command(command).await?;
+        if !control::A8MiniAbsoluteZoomAck::from_frame(&ack_frame)?.accepted {
+            return Err(A8MiniError::Rejected { cmd_id: ack_frame.cmd_id });
+        }
+
+        let target = zoom_tenths / 10.0;
+        let deadline = Instant::now() + constants::STATE_CONFIRM_TIMEOUT;
+        loop {
+            let current = self.get_current_zoom().await?;
+            if current == target {
+                return Ok(current);
+            }
+            if Instant::now() >= deadline {
+                warn!(target, current, "zoom did not reach target");
+                return Err(A8MiniError::ZoomNotReached { target, reached: current });
+            }
+            sleep(constants::STATE_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Auto focuses on the video pixel at (`x`, `y`), e.g. where an operator clicked on the live view.
+    ///
+    /// Returns `A8MiniError::Rejected` if the camera does not start focusing.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn focus_on_point(&self, x: u16, y: u16) -> Result<(), A8MiniError> {
+        let command = control::A8MiniComplexCommand::AutoFocusAt { x, y };
+        let ack_frame = self.send_command(command).await?;
+        if !control::A8MiniAutoFocusAck::from_frame(&ack_frame)?.accepted {
+            return Err(A8MiniError::Rejected { cmd_id: ack_frame.cmd_id });
+        }
+
+        Ok(())
+    }
+
+    /// Retrieves the encoding settings of `stream_type`.
+    pub async fn get_codec(&self, stream_type: control::StreamType) -> Result<control::CodecSpec, A8MiniError> {
+        let codec_frame = self
+            .send_command(control::A8MiniComplexCommand::GetCodecSpecs(stream_type))
+            .await?;
+        Ok(control::CodecSpec::from_frame(&codec_frame)?)
+    }
+
+    /// Applies `spec` to its stream and reads the settings back. Returns the spec the camera reports as applied.
+    ///
+    /// The frame rate cannot be set, so a spec whose frame rate differs from the stream's current one is refused
+    /// with `A8MiniError::UnsupportedCodec` before anything is sent. Returns `A8MiniError::Rejected` if the camera
+    /// refuses the settings and `A8MiniError::CodecMismatch` if it reports different ones afterwards.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn set_codec(&self, spec: control::CodecSpec) -> Result<control::CodecSpec, A8MiniError> {
+        let current = self.get_codec(spec.stream_type()).await?;
+        if current.frame_rate() != spec.frame_rate() {
+            return Err(A8MiniError::UnsupportedCodec(format!(
+                "the {:?} stream runs at {} fps and its frame rate cannot be changed to {} fps",
+                spec.stream_type(),
+                current.frame_rate(),
+                spec.frame_rate()
+            )));
+        }
+
+        let ack_frame = self
+            .send_command(control::A8MiniComplexCommand::SetCodecSpecs(spec))
+            .await?;
+        if !control::A8MiniCodecAck::from_frame(&ack_frame)?.accepted {
+            return Err(A8MiniError::Rejected { cmd_id: ack_frame.cmd_id });
+        }
+
+        let applied = self.get_codec(spec.stream_type()).await?;
+        if applied != spec {
+            return Err(A8MiniError::CodecMismatch { requested: spec, applied });
+        }
+
+        Ok(applied)
+    }
+
+    /// Applies `preset` to `stream_type`. Returns the spec the camera reports as applied, see `set_codec`; like
+    /// there, a stream not running at the preset's frame rate is refused with `A8MiniError::UnsupportedCodec`.
+    pub async fn set_video_preset(
+        &self,
+        stream_type: control::StreamType,
+        preset: control::VideoPreset,
+    ) -> Result<control::CodecSpec, A8MiniError> {
+        self.set_codec(preset.spec(stream_type)?).await
+    }
+
+    /// Retrieves the camera's recording, HDR and video output state along with the gimbal configuration.
+    pub async fn get_camera_status(&self) -> Result<control::GimbalConfig, A8MiniError> {
+        let status_frame = self
+            .send_command(control::A8MiniSimpleCommand::CameraInformation)
+            .await?;
+        Ok(control::GimbalConfig::from_frame(&status_frame)?)
+    }
+
+    /// Starts recording if the camera is not recording already, and waits until the camera reports it is.
+    ///
+    /// Returns `A8MiniError::RecordingUnchanged` if the camera does not start recording, e.g. without a TF card.
+    pub async fn start_recording(&self) -> Result<(), A8MiniError> {
+        self.set_recording(true).await
+    }
+
+    /// Stops recording if the camera is recording, and waits until the camera reports it stopped.
+    pub async fn stop_recording(&self) -> Result<(), A8MiniError> {
+        self.set_recording(false).await
+    }
+
+    /// Sends the `RecordVideo` toggle only if the recording state differs from `recording`, then waits for function
+    /// feedback or polls the camera status until it matches or `constants::STATE_CONFIRM_TIMEOUT` elapses.
+    #[instrument(level = "debug", skip(self))]
+    async fn set_recording(&self, recording: bool) -> Result<(), A8MiniError> {
+        let status = self.get_camera_status().await?;
+        if status.is_recording() == recording {
+            debug!(state = ?status.recording, "recording state already as requested");
+            return Ok(());
+        }
+
+        let mut events = self.subscribe_events();
+        self.send_command_blind(control::A8MiniSimpleCommand::RecordVideo).await?;
+
+        let deadline = Instant::now() + constants::STATE_CONFIRM_TIMEOUT;
+        let mut state = status.recording;
+        while Instant::now() < deadline {
+            sleep(constants::STATE_POLL_INTERVAL).await;
+
+            while let Ok(event) = events.try_recv() {
+                match event {
+                    control::CameraEvent::RecordingStarted if recording => return Ok(()),
+                    control::CameraEvent::RecordingStopped if !recording => return Ok(()),
+                    control::CameraEvent::RecordingFailed => {
+                        warn!(recording, "camera reported recording failure");
+                        let state = self.get_camera_status().await.map_or(state, |status| status.recording);
+                        return Err(A8MiniError::RecordingUnchanged { recording, state });
+                    }
+                    _ => {}
+                }
+            }
+
+            match self.get_camera_status().await {
+                Ok(status) if status.is_recording() == recording => return Ok(()),
+                Ok(status) => state = status.recording,
+                Err(A8MiniError::Timeout) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        warn!(recording, ?state, "recording state did not change");
+        Err(A8MiniError::RecordingUnchanged { recording, state })
+    }
+
+    /// Retrieves the gimbal's active motion mode. Fails with `A8MiniError::MalformedFrame` if the camera reports
+    /// a mode this crate does not know.
+    pub async fn get_gimbal_mode(&self) -> Result<control::GimbalMode, A8MiniError> {
+        let status_frame = self
+            .send_command(control::A8MiniSimpleCommand::CameraInformation)
+            .await?;
+        let status = control::GimbalConfig::from_frame(&status_frame)?;
+        status.motion_mode.ok_or_else(|| {
+            A8MiniError::MalformedFrame(frame::FrameError::InvalidField {
+                cmd_id: status_frame.cmd_id,
+                field: "gimbal motion mode",
+                value: status_frame.data[4],
+            })
+        })
+    }
+
+    /// Switches the gimbal to `mode` and polls the camera status until it reports `mode` or
+    /// `constants::STATE_CONFIRM_TIMEOUT` elapses.
+    ///
+    /// Returns `A8MiniError::GimbalModeUnchanged` if the camera stays in another or an unknown mode.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn set_gimbal_mode(&self, mode: control::GimbalMode) -> Result<(), A8MiniError> {
+        self.send_command_blind(control::A8MiniSimpleCommand::from(mode)).await?;
+
+        let deadline = Instant::now() + constants::STATE_CONFIRM_TIMEOUT;
+        let mut current = None;
+        loop {
+            match self.get_camera_status().await {
+                Ok(status) if status.motion_mode == Some(mode) => return Ok(()),
+                Ok(status) => current = Some(status.motion_mode),
+                Err(A8MiniError::Timeout) => {}
+                Err(e) => return Err(e),
+            }
+
+            if Instant::now() >= deadline {
+                break;
+            }
+            sleep(constants::STATE_POLL_INTERVAL).await;
+        }
+
+        let current = match current {
+            Some(current) => current,
+            None => self.get_camera_status().await?.motion_mode,
+        };
+        warn!(requested = ?mode, mode = ?current, "gimbal mode did not change");
+        Err(A8MiniError::GimbalModeUnchanged { requested: mode, mode: current })
+    }
+
+    /// Takes a photo and downloads it once the camera has stored it.
+    ///
+    /// Waits for the camera's function feedback or for the photo count to increase, whichever comes first, for up
+    /// to `constants::PHOTO_CAPTURE_TIMEOUT`. Returns `A8MiniError::PhotoFailed` if the camera reports a failure.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn capture_photo(&self) -> Result<media::CapturedPhoto, A8MiniError> {
+        let photo_count = self.media.photo_count().await?;
+        let mut events = self.subscribe_events();
+        self.send_command_blind(control::A8MiniSimpleCommand::TakePicture).await?;
+
+        let deadline = Instant::now() + constants::PHOTO_CAPTURE_TIMEOUT;
+        loop {
+            // Poll the count at least every interval, but right away once the camera reports the photo
+            if let Ok(Ok(event)) = timeout(constants::STATE_POLL_INTERVAL, events.recv()).await {
+                match event {
+                    control::CameraEvent::PhotoFailed => {
+                        warn!("camera reported photo failure");
+                        return Err(A8MiniError::PhotoFailed);
+                    }
+                    control::CameraEvent::PhotoTaken => debug!("camera reported photo taken"),
+                    _ => {}
+                }
+            }
+
+            let new_photo_count = self.media.photo_count().await?;
+            if new_photo_count > photo_count {
+                return self.media.fetch_photo(new_photo_count).await;
+            }
+
+            if Instant::now() >= deadline {
+                warn!(photo_count, "photo count did not increase");
+                return Err(A8MiniError::Timeout);
+            }
+        }
+    }
+
+    /// Retrieves the firmware versions of the camera's boards.
+    pub async fn get_firmware_version(&self) -> Result<control::A8MiniFirmware, A8MiniError> {
+        let firmware_frame = self
+            .send_command(control::A8MiniSimpleCommand::FirmwareVersionInformation)
+            .await?;
+        Ok(control::A8MiniFirmware::from_frame(&firmware_frame)?)
+    }
+
+    /// Retrieves the camera's hardware ID and the product model it encodes.
+    pub async fn get_hardware_id(&self) -> Result<control::A8MiniHardwareId, A8MiniError> {
+        let hardware_id_frame = self
+            .send_command(control::A8MiniSimpleCommand::HardwareIDInformation)
+            .await?;
+        Ok(control::A8MiniHardwareId::from_frame(&hardware_id_frame)?)
+    }
+
     /// Sends a `control::HTTPQuery` and returns the corresponding received `control::HTTPResponse`.
     pub async fn send_http_query<T: control::HTTPQuery>(
         &self,
         query: T,
-    ) -> Result<control::HTTPResponse, Box<dyn Error>> {
-        let response = reqwest::get(query.to_string()).await?;
-        println!("[HTTP] Waiting for response.");
-
-        let json = response.json::<control::HTTPResponse>().await?;
-        println!("[HTTP] Received response.");
-        Ok(json)
+    ) -> Result<control::HTTPResponse, A8MiniError> {
+        self.media.query(query).await
     }
 
     /// Retrieves an image or video (WIP) from the camera.
     pub async fn send_http_media_query<T: control::HTTPQuery>(
         &self,
         query: T,
-    ) -> Result<Vec<u8>, Box<dyn Error>> {
-        let response = reqwest::get(query.to_string()).await?;
-        println!("[HTTP] Waiting for response.");
-
-        let image_bytes = response.bytes().await?;
-        println!("[HTTP] Received response.");
-        Ok(image_bytes.to_vec())
+    ) -> Result<Vec<u8>, A8MiniError> {
+        self.media.download(query).await
     }
 }
 
+/// Binds a UDP socket to `addr`.
+async fn bind(addr: SocketAddr) -> Result<UdpSocket, A8MiniError> {
+    UdpSocket::bind(addr)
+        .await
+        .map_err(|source| A8MiniError::Bind { addr: addr.to_string(), source })
+}
+
+/// Parses a port given as a string.
+fn parse_port(port: &str) -> Result<u16, A8MiniError> {
+    port.parse()
+        .map_err(|_| A8MiniError::InvalidConfig(format!("invalid port {:?}", port)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::control::*;
 
+    use std::error::Error;
     use std::thread::sleep;
     use std::time::Duration;
     use tokio::fs::File;
     use tokio::io::AsyncWriteExt;
 
+    /// Connects to a freshly spawned emulator, returning its state for inspection.
+    async fn connect_emulated() -> Result<(A8Mini, Arc<std::sync::Mutex<emulator::EmulatorState>>), Box<dyn Error>> {
+        let emulator = emulator::Emulator::bind("127.0.0.1:0").await?;
+        let media_server = emulator.media_server("127.0.0.1:0").await?;
+        let port = emulator.local_addr()?.port().to_string();
+        let http_port = media_server.local_addr()?.port().to_string();
+        let state = emulator.state();
+        emulator.spawn();
+        media_server.spawn();
+
+        Ok((A8Mini::connect_to("127.0.0.1", &port, &http_port, "0", "0").await?, state))
+    }
+
+    /// Spawns a fake camera on an ephemeral port, answering every request with the datagrams `respond` returns.
+    /// Returns the port and the responder task, which runs until aborted.
+    ///
+    /// `SetTimeUTC` is acknowledged without calling `respond`, so the clock sync on connect does not interfere.
+    async fn spawn_fake_camera<F>(mut respond: F) -> Result<(u16, JoinHandle<()>), Box<dyn Error>>
+    where
+        F: FnMut(&frame::Frame) -> Vec<Vec<u8>> + Send + 'static,
+    {
+        let fake_camera = UdpSocket::bind("127.0.0.1:0").await?;
+        let port = fake_camera.local_addr()?.port();
+
+        let responder = tokio::spawn(async move {
+            let mut buf = [0; constants::RECV_BUFF_SIZE];
+            loop {
+                let (len, peer) = fake_camera.recv_from(&mut buf).await.unwrap();
+                let request = frame::Frame::from_bytes(&buf[..len]).unwrap();
+                let datagrams = match request.cmd_id {
+                    0x30 => vec![frame::Frame::new(0x30, vec![1]).with_seq(request.seq).to_bytes()],
+                    _ => respond(&request),
+                };
+                for datagram in datagrams {
+                    fake_camera.send_to(&datagram, peer).await.unwrap();
+                }
+            }
+        });
+        Ok((port, responder))
+    }
+
+    /// Connects to a fake camera on `port` with default settings.
+    async fn connect_fake(port: u16) -> Result<A8Mini, A8MiniError> {
+        A8Mini::connect_to("127.0.0.1", &port.to_string(), "82", "0", "0").await
+    }
+
+    #[tokio::test]
+    async fn test_emulated_take_and_download_photo() -> Result<(), Box<dyn Error>> {
+        let (cam, _) = connect_emulated().await?;
+        assert!(cam.http_base_url().starts_with("http://127.0.0.1:"));
+
+        cam.send_command_blind(control::A8MiniSimpleCommand::TakePicture).await?;
+        cam.get_attitude_information().await?;
+        let num_pictures = cam
+            .send_http_query(control::A8MiniSimpleHTTPQuery::GetMediaCountPhotos)
+            .await?
+            .data
+            .count
+            .unwrap();
+        let picture_bytes = cam
+            .send_http_media_query(control::A8MiniComplexHTTPQuery::GetPhoto(num_pictures as u32))
+            .await?;
+
+        assert_eq!(num_pictures, 1);
+        assert_eq!(picture_bytes, emulator::synthetic_jpeg(1));
+
+        let missing = cam.send_http_media_query(control::A8MiniComplexHTTPQuery::GetVideo(1)).await;
+        assert!(matches!(missing, Err(A8MiniError::HttpStatus { .. })));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_emulated_send_command_with_ack() -> Result<(), Box<dyn Error>> {
+        let (cam, _) = connect_emulated().await?;
+
+        cam.send_command(A8MiniComplexCommand::SetYawPitchAngle(-300, -450)).await?;
+        let attitude = cam.get_attitude_information().await?;
+        assert_eq!((attitude.theta_yaw, attitude.theta_pitch), (-300, -450));
+
+        cam.send_command(A8MiniSimpleCommand::AutoCenter).await?;
+        let attitude = cam.get_attitude_information().await?;
+        assert_eq!((attitude.yaw_deg(), attitude.pitch_deg()), (0.0, 0.0));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_emulated_zoom_to() -> Result<(), Box<dyn Error>> {
+        let (cam, state) = connect_emulated().await?;
+
+        assert_eq!(cam.get_max_zoom().await?, 6.0);
+        assert_eq!(cam.get_current_zoom().await?, 1.0);
+        assert_eq!(cam.zoom_to(2.96).await?, 3.0);
+        assert_eq!(cam.get_current_zoom().await?, 3.0);
+
+        assert_eq!(cam.zoom_in().await?, 3.1);
+        assert_eq!(cam.zoom_out().await?, 3.0);
+        assert_eq!(cam.zoom_out().await?, 2.9);
+
+        for zoom in [0.5, 6.1, f32::NAN] {
+            assert!(matches!(cam.zoom_to(zoom).await, Err(A8MiniError::ZoomOutOfRange { max, .. }) if max == 6.0));
+        }
+        assert_eq!(state.lock().unwrap().zoom, 2.9);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_zoom_to_fails_if_target_not_reached() -> Result<(), Box<dyn Error>> {
+        // A camera acknowledging any zoom but stuck at 2.5x
+        let (port, responder) = spawn_fake_camera(|request| {
+            let data = match request.cmd_id {
+                0x16 => vec![6, 0],
+                0x0f => vec![1],
+                0x18 => vec![2, 5],
+                _ => return vec![],
+            };
+            vec![frame::Frame::new(request.cmd_id, data).with_seq(request.seq).to_bytes()]
+        })
+        .await?;
+        let cam = connect_fake(port).await?;
+
+        let result = cam.zoom_to(4.0).await;
+        assert!(matches!(result, Err(A8MiniError::ZoomNotReached { target, reached }) if target == 4.0 && reached == 2.5));
+        assert_eq!(cam.zoom_to(2.5).await?, 2.5);
+        responder.abort();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_emulated_focus() -> Result<(), Box<dyn Error>> {
+        let (cam, state) = connect_emulated().await?;
+
+        cam.focus_on_point(960, 540).await?;
+        assert_eq!(state.lock().unwrap().focus_point, Some((960, 540)));
+
+        cam.send_command(control::A8MiniComplexCommand::ManualFocus(-3)).await?;
+        cam.send_command(control::A8MiniComplexCommand::ManualFocus(0)).await?;
+        assert_eq!(state.lock().unwrap().focus, -1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_emulated_get_and_set_codec() -> Result<(), Box<dyn Error>> {
+        use control::{CodecSpec, StreamType, VideoEncoding, VideoPreset};
+        let (cam, state) = connect_emulated().await?;
+
+        let recording = cam.get_codec(StreamType::Recording).await?;
+        assert_eq!(recording, CodecSpec::new(StreamType::Recording, VideoEncoding::H265, 3840, 2160, 15000, 30)?);
+
+        let spec = CodecSpec::new(StreamType::Sub, VideoEncoding::H264, 1920, 1080, 3000, 30)?;
+        assert_eq!(cam.set_codec(spec).await?, spec);
+        assert_eq!(state.lock().unwrap().codecs[2], (1, 1920, 1080, 3000, 30));
+
+        // The frame rate cannot be set, so a stream running at 60 fps is left untouched
+        state.lock().unwrap().codecs[1].4 = 60;
+        let main = state.lock().unwrap().codecs[1];
+        let spec = CodecSpec::new(StreamType::Main, VideoEncoding::H265, 1280, 720, 2000, 30)?;
+        let result = cam.set_codec(spec).await;
+        assert!(matches!(result, Err(A8MiniError::UnsupportedCodec(_))));
+        assert_eq!(state.lock().unwrap().codecs[1], main);
+        state.lock().unwrap().codecs[1].4 = 30;
+        assert_eq!(cam.get_codec(StreamType::Recording).await?, recording);
+
+        let applied = cam.set_video_preset(StreamType::Main, VideoPreset::Hd720p30).await?;
+        assert_eq!(VideoPreset::from_spec(&applied), Some(VideoPreset::Hd720p30));
+        assert!(matches!(
+            cam.set_video_preset(StreamType::Main, VideoPreset::Uhd4k30).await,
+            Err(A8MiniError::UnsupportedCodec(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_emulated_video_presets_round_trip() -> Result<(), Box<dyn Error>> {
+        use control::{StreamType, VideoPreset};
+        let (cam, state) = connect_emulated().await?;
+
+        for stream_type in [StreamType::Recording, StreamType::Main, StreamType::Sub] {
+            for preset in VideoPreset::ALL {
+                if preset == VideoPreset::Uhd4k30 && stream_type != StreamType::Recording {
+                    continue;
+                }
+
+                let applied = cam.set_video_preset(stream_type, preset).await?;
+                let reported = cam.get_codec(stream_type).await?;
+                assert_eq!(reported, applied);
+                assert_eq!(VideoPreset::from_spec(&reported), Some(preset));
+                assert_eq!(reported.bitrate_kbps(), preset.bitrate_kbps());
+            }
+        }
+
+        // The frame rate cannot be set, so a stream at 60 fps does not take a 30 fps preset
+        state.lock().unwrap().codecs[2].4 = 60;
+        let sub = state.lock().unwrap().codecs[2];
+        let result = cam.set_video_preset(StreamType::Sub, VideoPreset::Fhd1080p30).await;
+        assert!(matches!(result, Err(A8MiniError::UnsupportedCodec(_))));
+        assert_eq!(state.lock().unwrap().codecs[2], sub);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_emulated_gimbal_mode() -> Result<(), Box<dyn Error>> {
+        let (cam, state) = connect_emulated().await?;
+        assert_eq!(cam.get_gimbal_mode().await?, control::GimbalMode::Follow);
+
+        for mode in [control::GimbalMode::Lock, control::GimbalMode::Fpv, control::GimbalMode::Follow] {
+            cam.set_gimbal_mode(mode).await?;
+            assert_eq!(cam.get_gimbal_mode().await?, mode);
+        }
+        assert_eq!(state.lock().unwrap().motion_mode, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_emulated_sync_clock() -> Result<(), Box<dyn Error>> {
+        let (cam, state) = connect_emulated().await?;
+
+        // Synced on connect by default
+        timeout(Duration::from_secs(2), async {
+            while state.lock().unwrap().utc_time_us.is_none() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await?;
+        cam.stop_clock_sync();
+
+        let synced = cam.sync_clock().await?;
+        assert_eq!(state.lock().unwrap().utc_time_us, Some(synced.timestamp_micros() as u64));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_clock_sync_on_connect() -> Result<(), Box<dyn Error>> {
+        let emulator = emulator::Emulator::bind("127.0.0.1:0").await?;
+        let config = config::A8MiniConfig::default()
+            .with_camera_ip("127.0.0.1")
+            .with_camera_command_port(emulator.local_addr()?.port())
+            .with_clock_sync(config::ClockSyncOptions {
+                interval: Duration::from_millis(50),
+                ..config::ClockSyncOptions::default()
+            });
+        let state = emulator.state();
+        emulator.spawn();
+
+        let before = Utc::now().timestamp_micros() as u64;
+        let cam: A8Mini = A8Mini::connect_with(config).await?;
+
+        // Synced right away, then again every interval
+        let mut synced = Vec::new();
+        timeout(Duration::from_secs(2), async {
+            while synced.len() < 2 {
+                let utc_time_us = state.lock().unwrap().utc_time_us;
+                if let Some(utc_time_us) = utc_time_us.filter(|time| synced.last() != Some(time)) {
+                    synced.push(utc_time_us);
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await?;
+        assert!(before <= synced[0] && synced[0] < synced[1]);
+
+        cam.stop_clock_sync();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_emulated_start_and_stop_recording() -> Result<(), Box<dyn Error>> {
+        let (cam, state) = connect_emulated().await?;
+
+        cam.start_recording().await?;
+        assert!(state.lock().unwrap().recording);
+
+        // Starting twice must not toggle recording off again
+        cam.start_recording().await?;
+        assert!(state.lock().unwrap().recording);
+
+        cam.stop_recording().await?;
+        cam.stop_recording().await?;
+        let state = state.lock().unwrap().clone();
+        assert!(!state.recording);
+        assert_eq!(state.video_count, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_emulated_capture_photo() -> Result<(), Box<dyn Error>> {
+        let (cam, state) = connect_emulated().await?;
+        state.lock().unwrap().photo_count = 6;
+
+        let photo = cam.capture_photo().await?;
+        assert_eq!(photo.index, 7);
+        assert_eq!(photo.file_name, "IMG_0007.jpg");
+        assert_eq!(photo.bytes, emulator::synthetic_jpeg(7));
+
+        state.lock().unwrap().tf_card = false;
+        assert!(matches!(cam.capture_photo().await, Err(A8MiniError::PhotoFailed)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_emulated_camera_events() -> Result<(), Box<dyn Error>> {
+        let (cam, state) = connect_emulated().await?;
+        let mut events = cam.subscribe_events();
+
+        cam.send_command_blind(A8MiniSimpleCommand::TakePicture).await?;
+        cam.send_command_blind(A8MiniSimpleCommand::RecordVideo).await?;
+        cam.send_command_blind(A8MiniSimpleCommand::RecordVideo).await?;
+
+        let mut received = Vec::new();
+        for _ in 0..3 {
+            received.push(timeout(Duration::from_secs(1), events.recv()).await??);
+        }
+        assert_eq!(
+            received,
+            [CameraEvent::PhotoTaken, CameraEvent::RecordingStarted, CameraEvent::RecordingStopped]
+        );
+
+        state.lock().unwrap().tf_card = false;
+        cam.send_command_blind(A8MiniSimpleCommand::TakePicture).await?;
+        assert_eq!(timeout(Duration::from_secs(1), events.recv()).await??, CameraEvent::PhotoFailed);
+
+        // Without a TF card the camera reports the failure instead of starting to record
+        let result = cam.start_recording().await;
+        assert!(matches!(
+            result,
+            Err(A8MiniError::RecordingUnchanged { recording: true, state: RecordingState::NoTfCard })
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_start_recording_fails_if_state_unchanged() -> Result<(), Box<dyn Error>> {
+        // A camera without a TF card ignores the toggle
+        let (port, responder) = spawn_fake_camera(|request| match request.cmd_id {
+            0x0a => vec![frame::Frame::new(0x0a, vec![0, 0, 0, 2, 1, 1, 0]).with_seq(request.seq).to_bytes()],
+            _ => vec![],
+        })
+        .await?;
+        let cam = connect_fake(port).await?;
+
+        let result = cam.start_recording().await;
+        assert!(matches!(
+            result,
+            Err(A8MiniError::RecordingUnchanged { recording: true, state: RecordingState::NoTfCard })
+        ));
+        responder.abort();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_set_gimbal_mode_fails_if_mode_unchanged() -> Result<(), Box<dyn Error>> {
+        // A camera stuck in Follow mode
+        let (port, responder) = spawn_fake_camera(|request| match request.cmd_id {
+            0x0a => vec![frame::Frame::new(0x0a, vec![0, 0, 0, 0, 1, 1, 0]).with_seq(request.seq).to_bytes()],
+            _ => vec![],
+        })
+        .await?;
+        let cam = connect_fake(port).await?;
+
+        let result = cam.set_gimbal_mode(control::GimbalMode::Lock).await;
+        assert!(matches!(
+            result,
+            Err(A8MiniError::GimbalModeUnchanged { requested: control::GimbalMode::Lock, mode: Some(control::GimbalMode::Follow) })
+        ));
+        responder.abort();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_camera_status_tolerates_unknown_values() -> Result<(), Box<dyn Error>> {
+        // Unknown HDR, motion mode and video output bytes
+        let (port, responder) = spawn_fake_camera(|request| match request.cmd_id {
+            0x0a => vec![frame::Frame::new(0x0a, vec![0, 7, 0, 1, 9, 1, 5]).with_seq(request.seq).to_bytes()],
+            _ => vec![],
+        })
+        .await?;
+        let cam = connect_fake(port).await?;
+
+        let status = cam.get_camera_status().await?;
+        assert!(status.is_recording());
+        assert_eq!(status.video_output, VideoOutput::Unknown(5));
+        cam.start_recording().await?;
+
+        assert!(matches!(
+            cam.get_gimbal_mode().await,
+            Err(A8MiniError::MalformedFrame(frame::FrameError::InvalidField { value: 9, .. }))
+        ));
+        assert!(matches!(
+            cam.set_gimbal_mode(control::GimbalMode::Lock).await,
+            Err(A8MiniError::GimbalModeUnchanged { mode: None, .. })
+        ));
+        responder.abort();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_emulated_video_output_off() -> Result<(), Box<dyn Error>> {
+        let (cam, state) = connect_emulated().await?;
+
+        cam.send_command_blind(A8MiniSimpleCommand::SetVideoOutputOff).await?;
+        assert_eq!(cam.get_camera_status().await?.video_output, VideoOutput::Off);
+        assert_eq!(state.lock().unwrap().video_output, 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_emulated_firmware_and_hardware_id() -> Result<(), Box<dyn Error>> {
+        let (cam, _) = connect_emulated().await?;
+
+        let firmware = cam.get_firmware_version().await?;
+        assert_eq!(firmware.camera.to_string(), "3.2.3");
+        assert_eq!(firmware.gimbal.to_string(), "3.1.1");
+        assert_eq!(firmware.zoom.map(|zoom| zoom.to_string()).as_deref(), Some("1.0.2"));
+
+        let hardware_id = cam.get_hardware_id().await?;
+        assert_eq!(hardware_id.id, "7300000000ab");
+        assert_eq!(hardware_id.model, Some(ProductModel::A8Mini));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_emulated_send_commands_blind() -> Result<(), Box<dyn Error>> {
+        let (cam, state) = connect_emulated().await?;
+
+        cam.send_command_blind(A8MiniSimpleCommand::TakePicture).await?;
+        cam.send_command_blind(A8MiniSimpleCommand::RecordVideo).await?;
+        cam.send_command_blind(A8MiniSimpleCommand::SetFPVMode).await?;
+        cam.send_command_blind(A8MiniComplexCommand::SetYawPitchSpeed(-25, 15)).await?;
+
+        // Blind commands have no ACK; a round trip guarantees the emulator processed them
+        let attitude = cam.get_attitude_information().await?;
+        assert_eq!((attitude.v_yaw, attitude.v_pitch), (-250, 150));
+
+        let status = cam.get_camera_status().await?;
+        assert!(status.is_recording());
+        assert_eq!(status.motion_mode, Some(GimbalMode::Fpv));
+
+        let state = state.lock().unwrap().clone();
+        assert_eq!(state.photo_count, 1);
+        assert!(state.recording);
+        assert_eq!(state.motion_mode, 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_send_command_discards_stale_replies() -> Result<(), Box<dyn Error>> {
+        let reply = |cmd_id: u8, seq: u16, yaw: u8| {
+            let mut data = vec![0; 12];
+            data[0] = yaw;
+            frame::Frame::new(cmd_id, data).with_seq(seq).to_bytes()
+        };
+
+        let mut answered = false;
+        let (port, responder) = spawn_fake_camera(move |request| {
+            if std::mem::replace(&mut answered, true) {
+                return vec![reply(0x0d, request.seq, 5)];
+            }
+
+            // A reply to a different command, a truncated datagram, then the real answer and a duplicate of it
+            vec![
+                reply(0x0a, request.seq, 1),
+                vec![0x55, 0x66, 0x02],
+                reply(0x0d, request.seq, 2),
+                reply(0x0d, request.seq, 3),
+            ]
+        })
+        .await?;
+        let cam = connect_fake(port).await?;
+
+        assert_eq!(cam.get_attitude_information().await?.theta_yaw, 2);
+        // Let the duplicate arrive while nothing is pending so it is dropped
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(cam.get_attitude_information().await?.theta_yaw, 5);
+        responder.abort();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_connect_with_rejects_zero_heartbeat_interval() {
+        let heartbeat = config::HeartbeatOptions {
+            interval: Duration::ZERO,
+            ..config::HeartbeatOptions::default()
+        };
+        let config = config::A8MiniConfig::default()
+            .with_camera_ip("127.0.0.1")
+            .with_heartbeat(heartbeat);
+
+        let result = A8Mini::connect_with(config).await;
+        assert!(matches!(result, Err(A8MiniError::InvalidConfig(_))));
+    }
+
+    #[tokio::test]
+    async fn test_send_command_accepts_firmware_seq_counter() -> Result<(), Box<dyn Error>> {
+        // The camera numbers its replies itself; the first one happens to echo our SEQ
+        let mut camera_seq = None;
+        let (port, responder) = spawn_fake_camera(move |request| {
+            let seq = camera_seq.map_or(request.seq, |seq: u16| seq.wrapping_add(100));
+            camera_seq = Some(seq);
+            let mut data = vec![0; 12];
+            data[0] = seq as u8;
+            vec![frame::Frame::new(0x0d, data).with_seq(seq).to_bytes()]
+        })
+        .await?;
+        let cam = connect_fake(port).await?;
+
+        for _ in 0..3 {
+            cam.get_attitude_information().await?;
+        }
+        responder.abort();
+        Ok(())
+    }
+
+
+    #[tokio::test]
+    async fn test_send_command_reports_corrupted_reply() -> Result<(), Box<dyn Error>> {
+        let requests = Arc::new(AtomicU32::new(0));
+        let counted = Arc::clone(&requests);
+        let (port, responder) = spawn_fake_camera(move |request| {
+            counted.fetch_add(1, Ordering::Relaxed);
+            let mut reply = frame::Frame::new(0x0d, vec![0; 12]).with_seq(request.seq).to_bytes();
+            *reply.last_mut().unwrap() ^= 0xff;
+            vec![reply]
+        })
+        .await?;
+        let config = config::A8MiniConfig::default()
+            .with_camera_ip("127.0.0.1")
+            .with_camera_command_port(port)
+            .with_retry(config::RetryPolicy {
+                max_attempts: 3,
+                delay: Duration::ZERO,
+            });
+        let cam: A8Mini = A8Mini::connect_with(config).await?;
+
+        let result = cam.get_attitude_information().await;
+        assert!(matches!(result, Err(A8MiniError::CrcMismatch { .. })));
+        assert!(result.unwrap_err().is_protocol_error());
+        assert_eq!(requests.load(Ordering::Relaxed), 1);
+        responder.abort();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_send_command_times_out() -> Result<(), Box<dyn Error>> {
+        let silent_camera = UdpSocket::bind("127.0.0.1:0").await?;
+        let port = silent_camera.local_addr()?.port().to_string();
+        let cam: A8Mini = A8Mini::connect_to("127.0.0.1", &port, "82", "0", "0").await?;
+
+        let result = cam.get_attitude_information().await;
+        assert!(matches!(result, Err(A8MiniError::Timeout)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_send_command_retries_after_timeout() -> Result<(), Box<dyn Error>> {
+        // Drop the first attempt and answer the retry
+        let mut first_seq = None;
+        let (port, responder) = spawn_fake_camera(move |request| match first_seq {
+            None => {
+                first_seq = Some(request.seq);
+                vec![]
+            }
+            Some(first_seq) => {
+                assert_ne!(first_seq, request.seq);
+                vec![frame::Frame::new(0x0d, vec![0; 12]).with_seq(request.seq).to_bytes()]
+            }
+        })
+        .await?;
+        let config = config::A8MiniConfig::default()
+            .with_camera_ip("127.0.0.1")
+            .with_camera_command_port(port)
+            .with_recv_timeout(Duration::from_millis(100))
+            .with_retry(config::RetryPolicy {
+                max_attempts: 2,
+                delay: Duration::from_millis(10),
+            });
+        let cam: A8Mini = A8Mini::connect_with(config).await?;
+
+        cam.get_attitude_information().await?;
+        responder.abort();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_commands_receive_own_replies() -> Result<(), Box<dyn Error>> {
+        fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+        assert_shareable::<A8Mini>();
+
+        // Answer in reverse order, tagging each reply with its request's SEQ
+        let mut first: Option<frame::Frame> = None;
+        let (port, responder) = spawn_fake_camera(move |request| {
+            let Some(first) = first.take() else {
+                first = Some(request.clone());
+                return vec![];
+            };
+
+            [request, &first]
+                .into_iter()
+                .map(|request| {
+                    let mut data = vec![0; 12];
+                    data[..2].copy_from_slice(&(request.seq as i16).to_le_bytes());
+                    frame::Frame::new(0x0d, data).with_seq(request.seq).to_bytes()
+                })
+                .collect()
+        })
+        .await?;
+        let cam = connect_fake(port).await?;
+
+        let query = |cam: A8Mini| {
+            tokio::spawn(async move {
+                cam.send_command(control::A8MiniSimpleCommand::AttitudeInformation).await
+            })
+        };
+        let (a, b) = tokio::join!(query(cam.clone()), query(cam.clone()));
+
+        for reply in [a??, b??] {
+            let attitude = control::A8MiniAtittude::from_frame(&reply)?;
+            assert_eq!(attitude.theta_yaw as u16, reply.seq);
+        }
+        responder.abort();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_reports_link_status() -> Result<(), Box<dyn Error>> {
+        let emulator = emulator::Emulator::bind("127.0.0.1:0").await?;
+        let config = config::A8MiniConfig::default()
+            .with_camera_ip("127.0.0.1")
+            .with_camera_command_port(emulator.local_addr()?.port())
+            .with_recv_timeout(Duration::from_millis(50))
+            .with_heartbeat(config::HeartbeatOptions {
+                enabled: true,
+                interval: Duration::from_millis(20),
+                degraded_after: Duration::from_millis(100),
+                lost_after: Duration::from_millis(300),
+            });
+        let emulator = emulator.spawn();
+
+        let cam: A8Mini = A8Mini::connect_with(config).await?;
+        let mut link_status = cam.watch_link_status();
+        assert_eq!(*link_status.borrow(), LinkStatus::Lost);
+
+        async fn wait_for(link_status: &mut watch::Receiver<LinkStatus>, expected: LinkStatus) -> Result<(), Box<dyn Error>> {
+            timeout(Duration::from_secs(2), link_status.wait_for(|status| *status == expected)).await??;
+            Ok(())
+        }
+        wait_for(&mut link_status, LinkStatus::Connected).await?;
+        assert_eq!(cam.link_status(), LinkStatus::Connected);
+
+        emulator.abort();
+        wait_for(&mut link_status, LinkStatus::Degraded).await?;
+        wait_for(&mut link_status, LinkStatus::Lost).await?;
+        assert_eq!(cam.link_status(), LinkStatus::Lost);
+
+        cam.stop_heartbeat();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reestablish_rebinds_fixed_local_port() -> Result<(), Box<dyn Error>> {
+        let emulator = emulator::Emulator::bind("127.0.0.1:0").await?;
+        let local_port = UdpSocket::bind("127.0.0.1:0").await?.local_addr()?.port();
+        let config = config::A8MiniConfig::default()
+            .with_camera_ip("127.0.0.1")
+            .with_camera_command_port(emulator.local_addr()?.port())
+            .with_local_bind_addr("127.0.0.1".parse()?)
+            .with_local_command_port(local_port);
+        emulator.spawn();
+
+        let cam: A8Mini = A8Mini::connect_with(config).await?;
+        for _ in 0..3 {
+            cam.inner.reestablish().await?;
+            cam.get_attitude_information().await?;
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_detects_loss_again_after_giving_up() -> Result<(), Box<dyn Error>> {
+        let (port, responder) = spawn_fake_camera(|_| vec![]).await?;
+        let config = config::A8MiniConfig::default()
+            .with_camera_ip("127.0.0.1")
+            .with_camera_command_port(port)
+            .with_recv_timeout(Duration::from_millis(50))
+            .with_reconnect(config::ReconnectPolicy {
+                enabled: true,
+                max_consecutive_failures: 2,
+                initial_backoff: Duration::from_millis(10),
+                max_jitter: Duration::ZERO,
+                max_attempts: 1,
+                ..config::ReconnectPolicy::default()
+            });
+        let cam: A8Mini = A8Mini::connect_with(config).await?;
+        let mut events = cam.subscribe_reconnect_events();
+
+        for _ in 0..2 {
+            for _ in 0..2 {
+                assert!(cam.get_attitude_information().await.is_err());
+            }
+
+            let wait = Duration::from_secs(2);
+            assert_eq!(
+                timeout(wait, events.recv()).await??,
+                ReconnectEvent::LinkLost(LossReason::ConsecutiveFailures(2))
+            );
+            assert!(matches!(timeout(wait, events.recv()).await??, ReconnectEvent::Reconnecting { attempt: 1, .. }));
+            assert_eq!(timeout(wait, events.recv()).await??, ReconnectEvent::GaveUp { attempts: 1 });
+        }
+        responder.abort();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_and_replays_settings() -> Result<(), Box<dyn Error>> {
+        let emulator = emulator::Emulator::bind("127.0.0.1:0").await?;
+        let emulator_addr = emulator.local_addr()?;
+        let config = config::A8MiniConfig::default()
+            .with_camera_ip("127.0.0.1")
+            .with_camera_command_port(emulator_addr.port())
+            .with_recv_timeout(Duration::from_millis(50))
+            .with_reconnect(config::ReconnectPolicy {
+                enabled: true,
+                max_consecutive_failures: 2,
+                initial_backoff: Duration::from_millis(10),
+                max_backoff: Duration::from_millis(50),
+                max_jitter: Duration::from_millis(5),
+                max_attempts: 0,
+            });
+        let emulator = emulator.spawn();
+
+        let cam: A8Mini = A8Mini::connect_with(config).await?;
+        let mut events = cam.subscribe_reconnect_events();
+        cam.send_command_blind(A8MiniSimpleCommand::SetLockMode).await?;
+        let spec = control::CodecSpec::new(control::StreamType::Recording, control::VideoEncoding::H265, 1280, 720, 2000, 30)?;
+        cam.set_codec(spec).await?;
+
+        // The camera goes away; two failed requests in a row trigger the supervisor
+        emulator.abort();
+        let _ = emulator.await;
+        for _ in 0..2 {
+            assert!(cam.get_attitude_information().await.is_err());
+        }
+
+        assert_eq!(
+            timeout(Duration::from_secs(2), events.recv()).await??,
+            ReconnectEvent::LinkLost(LossReason::ConsecutiveFailures(2))
+        );
+        assert!(matches!(events.recv().await?, ReconnectEvent::Reconnecting { attempt: 1, .. }));
+
+        // The camera comes back on the same port
+        let emulator = emulator::Emulator::bind(emulator_addr).await?;
+        let state = emulator.state();
+        emulator.spawn();
+
+        loop {
+            match timeout(Duration::from_secs(2), events.recv()).await?? {
+                ReconnectEvent::Reconnecting { .. } => continue,
+                event => {
+                    assert!(matches!(event, ReconnectEvent::Reconnected { .. }));
+                    break;
+                }
+            }
+        }
+        cam.get_attitude_information().await?;
+
+        let state = state.lock().unwrap().clone();
+        assert_eq!(state.motion_mode, 0);
+        assert_eq!(state.codecs[0], (2, 1280, 720, 2000, 30));
+        Ok(())
+    }
 
     #[ignore]
     #[tokio::test]
@@ -277,9 +1683,9 @@ mod tests {
         // cam.send_command_blind(A8MiniSimpleCommand::RecordVideo).await?;
         // println!("{:?}", cam.send_http_query(A8MiniSimpleHTTPQuery::GetMediaCountVideos).await?);
 
-        // cam.send_command_blind(A8MiniComplexCommand::SetCodecSpecs(0, 2, 1920, 1080, 4000, 0)).await?;
+        // cam.set_codec(CodecSpec::new(StreamType::Recording, VideoEncoding::H265, 1920, 1080, 4000, 30)?).await?;
 
-        // cam.send_command_blind(A8MiniSimpleCommand::Resolution4k).await?;
+        // cam.set_video_preset(StreamType::Recording, VideoPreset::Uhd4k30).await?;
         cam.send_command_blind(A8MiniSimpleCommand::RecordVideo).await?;
         // sleep(Duration::from_millis(10000));
         // cam.send_command_blind(A8MiniSimpleCommand::RecordVideo).await?;
@@ -1,11 +1,12 @@
 use std::error::Error;
 use std::io;
 
-use a8mini_camera_rs::control::{A8MiniComplexCommand, A8MiniSimpleCommand, A8MiniSimpleHTTPQuery, A8MiniComplexHTTPQuery};
+use a8mini_camera_rs::control::{A8MiniComplexCommand, A8MiniSimpleCommand, A8MiniSimpleHTTPQuery, A8MiniComplexHTTPQuery, CodecSpec, StreamType, VideoEncoding, VideoPreset};
 use a8mini_camera_rs::A8Mini;
 use chrono::Utc;
 use tokio::fs::File;
 use tokio::io::AsyncWriteExt;
+use tracing_subscriber::EnvFilter;
 
 
 fn print_ascii_command_table() {
@@ -14,15 +15,19 @@ fn print_ascii_command_table() {
     "MaxZoomInformation", "FocusIn", "FocusOut", "TakePicture", "RecordVideo", "Rotate100100", "CameraInformation",
     "AutoFocus", "HardwareIDInformation", "FirmwareVersionInformation", "SetLockMode", "SetFollowMode", "SetFPVMode",
     "AttitudeInformation", "SetVideoOutputHDMI", "SetVideoOutputCVBS", "SetVideoOutputOff", "LaserRangefinderInformation", 
-    "RebootCamera", "RebootGimbal", "Resolution4k", "Heartbeat"
+    "RebootCamera", "RebootGimbal", "Heartbeat", "CurrentZoomInformation"
   ];
 
   let complex_commands = [
     "SetYawPitchSpeed(i8, i8)",
     "SetYawPitchAngle(i16, i16)",
+    "SetAbsoluteZoom(f32)",
+    "AutoFocusAt(u16, u16)",
+    "ManualFocus(i8)",
     "SetTimeUTC(u64)",
-    "GetCodecSpecs(u8)",
-    "SetCodecSpecs(u8, u8, u16, u16, u16, u8)",
+    "GetCodecSpecs(stream)",
+    "SetCodecSpecs(stream, enc, w, h, kbps, fps)",
+    "SetVideoPreset(stream, 4K30|1080p30|720p30)",
   ];
 
   let simple_queries = [
@@ -58,17 +63,29 @@ fn print_ascii_command_table() {
   }
 }
 
+/// Parses a stream type given as 0 (recording), 1 (main) or 2 (sub), defaulting to the recording stream.
+fn parse_stream_type(arg: Option<&&str>) -> StreamType {
+  match arg.copied() {
+    Some("1") => StreamType::Main,
+    Some("2") => StreamType::Sub,
+    _ => StreamType::Recording,
+  }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
+  tracing_subscriber::fmt()
+    .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+    .init();
+
   print_ascii_command_table();
   
   loop {
-    let full_command: &str;
     println!("Awaiting command:");
     let stdin = io::stdin();
     let buf = &mut String::new();
     stdin.read_line(buf)?;
-    full_command = buf.strip_suffix("\n").unwrap();
+    let full_command: &str = buf.strip_suffix("\n").unwrap();
 
     let destructured_command: Vec<&str> = full_command.split(" ").collect();
     let command: &str = destructured_command[0];
@@ -103,15 +120,15 @@ async fn main() -> Result<(), Box<dyn Error>> {
       "LaserRangefinderInformation" => Some(A8MiniSimpleCommand::LaserRangefinderInformation),
       "RebootCamera" => Some(A8MiniSimpleCommand::RebootCamera),
       "RebootGimbal" => Some(A8MiniSimpleCommand::RebootGimbal),
-      "Resolution4k" => Some(A8MiniSimpleCommand::Resolution4k),
       "Heartbeat" => Some(A8MiniSimpleCommand::Heartbeat),
+      "CurrentZoomInformation" => Some(A8MiniSimpleCommand::CurrentZoomInformation),
       _ => None,
     };
 
-    if simple_command_enum.is_some() {
-      println!("Sending Simple Command {:?}", simple_command_enum.unwrap());
+    if let Some(simple_command) = simple_command_enum {
+      println!("Sending Simple Command {:?}", simple_command);
       let camera: A8Mini = A8Mini::connect().await?;
-      camera.send_command_blind(simple_command_enum.unwrap()).await?;
+      camera.send_command_blind(simple_command).await?;
       continue;
     }
 
@@ -126,25 +143,64 @@ async fn main() -> Result<(), Box<dyn Error>> {
         let pitch: i16 = destructured_command[2].parse().unwrap_or(0);
         Some(A8MiniComplexCommand::SetYawPitchAngle(yaw, pitch))
       },
+      "SetAbsoluteZoom" => {
+        let zoom: f32 = destructured_command.get(1).and_then(|s| s.parse().ok()).unwrap_or(1.0);
+        Some(A8MiniComplexCommand::SetAbsoluteZoom(zoom))
+      },
+      "AutoFocusAt" => {
+        let x: u16 = destructured_command.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
+        let y: u16 = destructured_command.get(2).and_then(|s| s.parse().ok()).unwrap_or(0);
+        Some(A8MiniComplexCommand::AutoFocusAt { x, y })
+      },
+      "ManualFocus" => {
+        let direction: i8 = destructured_command.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
+        Some(A8MiniComplexCommand::ManualFocus(direction))
+      },
       "SetTimeUTC" => {
-        let epoch: u64 = destructured_command[1].parse().unwrap_or(0);
+        let epoch: u64 = destructured_command.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
         Some(A8MiniComplexCommand::SetTimeUTC(epoch))
       },
       "GetCodecSpecs" => {
-        let stream_type: u8 = destructured_command[1].parse().unwrap_or(0);
+        let stream_type = parse_stream_type(destructured_command.get(1));
         Some(A8MiniComplexCommand::GetCodecSpecs(stream_type))
       },
+      "SetVideoPreset" => {
+        let stream_type = parse_stream_type(destructured_command.get(1));
+        let preset = destructured_command.get(2).copied().unwrap_or("4K30");
+        match preset.parse::<VideoPreset>().and_then(|preset| preset.spec(stream_type)) {
+          Ok(spec) => Some(A8MiniComplexCommand::SetCodecSpecs(spec)),
+          Err(e) => {
+            println!("{}", e);
+            continue;
+          },
+        }
+      },
       "SetCodecSpecs" => {
-        let stream_type: u8 = destructured_command[1].parse().unwrap_or(0);
-        Some(A8MiniComplexCommand::SetCodecSpecs(stream_type, 2, 3840, 2160, 50000, 0))
+        let stream_type = parse_stream_type(destructured_command.get(1));
+        let encoding = match destructured_command.get(2) {
+          Some(&"1") => VideoEncoding::H264,
+          _ => VideoEncoding::H265,
+        };
+        let width: u16 = destructured_command.get(3).and_then(|s| s.parse().ok()).unwrap_or(3840);
+        let height: u16 = destructured_command.get(4).and_then(|s| s.parse().ok()).unwrap_or(2160);
+        let bitrate_kbps: u16 = destructured_command.get(5).and_then(|s| s.parse().ok()).unwrap_or(15000);
+        let frame_rate: u8 = destructured_command.get(6).and_then(|s| s.parse().ok()).unwrap_or(30);
+
+        match CodecSpec::new(stream_type, encoding, width, height, bitrate_kbps, frame_rate) {
+          Ok(spec) => Some(A8MiniComplexCommand::SetCodecSpecs(spec)),
+          Err(e) => {
+            println!("{}", e);
+            continue;
+          },
+        }
       },
       _ => None,
     };
 
-    if complex_command_enum.is_some() {
-      println!("Sending Complex Command {:?}", complex_command_enum.unwrap());
+    if let Some(complex_command) = complex_command_enum {
+      println!("Sending Complex Command {:?}", complex_command);
       let camera: A8Mini = A8Mini::connect().await?;
-      camera.send_command_blind(complex_command_enum.unwrap()).await?;
+      camera.send_command_blind(complex_command).await?;
       continue;
     }
 
@@ -156,10 +212,10 @@ async fn main() -> Result<(), Box<dyn Error>> {
       _ => None,
     };
 
-    if simple_query_enum.is_some() {
-      println!("Sending Simple HTTP Query {:?}", simple_query_enum.unwrap());
+    if let Some(simple_query) = simple_query_enum {
+      println!("Sending Simple HTTP Query {:?}", simple_query);
       let camera: A8Mini = A8Mini::connect().await?;
-      let response = camera.send_http_query(simple_query_enum.unwrap()).await?;
+      let response = camera.send_http_query(simple_query).await?;
       println!("{:?}", response);
       continue;
     }
@@ -167,17 +223,16 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let complex_query_enum: Option<A8MiniComplexHTTPQuery> = match command {
       "GetPhoto" => {
         let photo_ind: u32 = destructured_command[1].parse().unwrap_or(0);
-        Some(A8MiniComplexHTTPQuery::GetPhoto(photo_ind as u32))
+        Some(A8MiniComplexHTTPQuery::GetPhoto(photo_ind))
       }
       "GetVideo" => {
         let video_ind: u32 = destructured_command[1].parse().unwrap_or(0);
-        Some(A8MiniComplexHTTPQuery::GetVideo(video_ind as u32))
+        Some(A8MiniComplexHTTPQuery::GetVideo(video_ind))
       }
       _ => None,
     };
 
-    if complex_query_enum.is_some() {
-      let complex_query = complex_query_enum.unwrap();
+    if let Some(complex_query) = complex_query_enum {
       println!("Sending Complex HTTP Query {:?}", complex_query);
       let camera: A8Mini = A8Mini::connect().await?;
       
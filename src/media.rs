@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use tokio::time::Instant;
+use tracing::{debug, instrument, trace, warn};
+
+use crate::config::HttpClientOptions;
+use crate::control::{A8MiniComplexHTTPQuery, A8MiniSimpleHTTPQuery, HTTPQuery, HTTPResponse};
+use crate::A8MiniError;
+
+/// A photo downloaded from the camera.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedPhoto {
+    /// Camera-side index, as passed to `A8MiniComplexHTTPQuery::GetPhoto`.
+    pub index: u32,
+    /// Camera-side file name, e.g. `IMG_0007.jpg`.
+    pub file_name: String,
+    /// JPEG data.
+    pub bytes: Vec<u8>,
+}
+
+/// Client for the camera's HTTP media API, reusing connections across queries.
+///
+/// Cloning is cheap; clones share the underlying connection pool.
+#[derive(Debug, Clone)]
+pub struct MediaClient {
+    client: reqwest::Client,
+    base_url: Arc<str>,
+}
+
+impl MediaClient {
+    /// Creates a client rendering `HTTPQuery`s against `base_url` (e.g. `http://192.168.144.25:82`).
+    pub fn new(base_url: &str, options: &HttpClientOptions) -> Result<Self, A8MiniError> {
+        let client = reqwest::Client::builder()
+            .timeout(options.timeout)
+            .connect_timeout(options.connect_timeout)
+            .build()?;
+
+        Ok(MediaClient {
+            client,
+            base_url: base_url.trim_end_matches('/').into(),
+        })
+    }
+
+    /// Returns the client with `HTTPQuery`s rendered against `base_url` instead.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').into();
+        self
+    }
+
+    /// Base URL `HTTPQuery`s are rendered against.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Sends an `HTTPQuery` and returns the corresponding received `HTTPResponse`.
+    #[instrument(level = "debug", skip_all, fields(query = ?query))]
+    pub async fn query<T: HTTPQuery>(&self, query: T) -> Result<HTTPResponse, A8MiniError> {
+        let sent_at = Instant::now();
+        let response = self.get(query.to_url(&self.base_url)).await?;
+
+        let body = response.bytes().await?;
+        trace!(body = %String::from_utf8_lossy(&body), "received response body");
+        let json = serde_json::from_slice::<HTTPResponse>(&body)?;
+        debug!(
+            len = body.len(),
+            latency_ms = sent_at.elapsed().as_secs_f64() * 1000.0,
+            "received response"
+        );
+        Ok(json)
+    }
+
+    /// Downloads the image or video an `HTTPQuery` points to.
+    #[instrument(level = "debug", skip_all, fields(query = ?query))]
+    pub async fn download<T: HTTPQuery>(&self, query: T) -> Result<Vec<u8>, A8MiniError> {
+        let sent_at = Instant::now();
+        let response = self.get(query.to_url(&self.base_url)).await?;
+
+        let media_bytes = response.bytes().await?;
+        debug!(
+            len = media_bytes.len(),
+            latency_ms = sent_at.elapsed().as_secs_f64() * 1000.0,
+            "received media"
+        );
+        Ok(media_bytes.to_vec())
+    }
+
+    /// Number of photos stored on the camera.
+    pub async fn photo_count(&self) -> Result<u32, A8MiniError> {
+        let response = self.query(A8MiniSimpleHTTPQuery::GetMediaCountPhotos).await?;
+        Ok(response.data.count.unwrap_or(0).max(0) as u32)
+    }
+
+    /// Number of videos stored on the camera.
+    pub async fn video_count(&self) -> Result<u32, A8MiniError> {
+        let response = self.query(A8MiniSimpleHTTPQuery::GetMediaCountVideos).await?;
+        Ok(response.data.count.unwrap_or(0).max(0) as u32)
+    }
+
+    /// Downloads the photo with camera-side index `index`, counting from 1.
+    pub async fn fetch_photo(&self, index: u32) -> Result<CapturedPhoto, A8MiniError> {
+        let query = A8MiniComplexHTTPQuery::GetPhoto(index);
+        Ok(CapturedPhoto {
+            index,
+            file_name: query.file_name(),
+            bytes: self.download(query).await?,
+        })
+    }
+
+    /// Sends a GET request to `url`, failing on non-success status codes.
+    async fn get(&self, url: String) -> Result<reqwest::Response, A8MiniError> {
+        debug!(url = %url, "sending HTTP request");
+        let response = self.client.get(&url).send().await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            warn!(url = %url, status = %status, "HTTP request failed");
+            return Err(A8MiniError::HttpStatus { url, status });
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::emulator::{synthetic_jpeg, EmulatorState, MediaServer};
+
+    #[tokio::test]
+    async fn test_media_client_queries_and_checks_status() -> Result<(), Box<dyn std::error::Error>> {
+        let state = Arc::new(Mutex::new(EmulatorState::default()));
+        state.lock().unwrap().photo_count = 1;
+
+        let server = MediaServer::bind("127.0.0.1:0", state).await?;
+        let base_url = format!("http://{}/", server.local_addr()?);
+        server.spawn();
+
+        let client = MediaClient::new(&base_url, &HttpClientOptions::default())?;
+        assert_eq!(client.base_url(), base_url.trim_end_matches('/'));
+
+        let count = client.query(A8MiniSimpleHTTPQuery::GetMediaCountPhotos).await?;
+        assert_eq!(count.data.count, Some(1));
+        assert_eq!(client.download(A8MiniComplexHTTPQuery::GetPhoto(1)).await?, synthetic_jpeg(1));
+        assert_eq!((client.photo_count().await?, client.video_count().await?), (1, 0));
+
+        let photo = client.fetch_photo(1).await?;
+        assert_eq!(photo.file_name, "IMG_0001.jpg");
+        assert_eq!(photo.bytes, synthetic_jpeg(1));
+
+        let missing = client.download(A8MiniComplexHTTPQuery::GetPhoto(2)).await;
+        assert!(matches!(
+            missing,
+            Err(A8MiniError::HttpStatus { status, .. }) if status == reqwest::StatusCode::NOT_FOUND
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use tokio::sync::{watch, Notify};
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+use crate::frame::Frame;
+use crate::heartbeat::LinkStatus;
+use crate::Inner;
+
+/// CMD_ID of the camera function command, which selects the gimbal mode among other things.
+const CMD_FUNCTION: u8 = 0x0c;
+/// CMD_ID of the set codec command.
+const CMD_SET_CODEC: u8 = 0x21;
+
+/// Why the link was considered lost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LossReason {
+    /// The heartbeat task reported `LinkStatus::Lost`.
+    HeartbeatLost,
+    /// This many requests in a row timed out or could not be sent.
+    ConsecutiveFailures(u32),
+}
+
+impl fmt::Display for LossReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LossReason::HeartbeatLost => write!(f, "heartbeat lost"),
+            LossReason::ConsecutiveFailures(failures) => write!(f, "{} consecutive failed requests", failures),
+        }
+    }
+}
+
+/// Progress of the reconnect supervisor, broadcast to `A8Mini::subscribe_reconnect_events` subscribers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReconnectEvent {
+    /// The link was lost; reconnect attempts follow.
+    LinkLost(LossReason),
+    /// Waiting `backoff` before the `attempt`th attempt.
+    Reconnecting { attempt: u32, backoff: Duration },
+    /// The camera answered again after `attempts` attempts and the last settings were replayed.
+    Reconnected { attempts: u32 },
+    /// `config::ReconnectPolicy::max_attempts` attempts failed; the supervisor waits for the next loss.
+    GaveUp { attempts: u32 },
+}
+
+/// Settings requested through the link that are restored after a reconnect.
+#[derive(Debug, Default)]
+pub(crate) struct Replay {
+    gimbal_mode: Option<Frame>,
+    codecs: BTreeMap<u8, Frame>,
+}
+
+impl Replay {
+    /// Remembers `frame` if it requests a gimbal mode or codec settings.
+    pub(crate) fn record(&mut self, frame: &Frame) {
+        match (frame.cmd_id, frame.data.first()) {
+            (CMD_FUNCTION, Some(3..=5)) => self.gimbal_mode = Some(frame.clone()),
+            (CMD_SET_CODEC, Some(&stream_type)) => {
+                self.codecs.insert(stream_type, frame.clone());
+            }
+            _ => {}
+        }
+    }
+
+    /// Frames to resend, in the order they should be sent.
+    fn frames(&self) -> Vec<Frame> {
+        self.gimbal_mode.iter().chain(self.codecs.values()).cloned().collect()
+    }
+}
+
+/// Spawns the task watching for link loss and reconnecting `inner`. The link is lost when `link_status` turns
+/// `Lost` or when `link_lost` is notified with the consecutive failure limit reached.
+pub(crate) fn spawn(
+    inner: Weak<Inner>,
+    mut link_status: watch::Receiver<LinkStatus>,
+    link_lost: Arc<Notify>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            let reason = tokio::select! {
+                changed = link_status.changed() => {
+                    if changed.is_err() {
+                        return;
+                    }
+                    if *link_status.borrow_and_update() != LinkStatus::Lost {
+                        continue;
+                    }
+                    LossReason::HeartbeatLost
+                }
+                _ = link_lost.notified() => {
+                    let Some(inner) = inner.upgrade() else {
+                        return;
+                    };
+                    let failures = inner.consecutive_failures.load(Ordering::Relaxed);
+                    let max_failures = inner.config.reconnect.max_consecutive_failures;
+                    if max_failures == 0 || failures < max_failures {
+                        continue;
+                    }
+                    LossReason::ConsecutiveFailures(failures)
+                }
+            };
+
+            if !reconnect(&inner, reason).await {
+                return;
+            }
+
+            // Transitions seen while reconnecting are stale
+            link_status.borrow_and_update();
+        }
+    })
+}
+
+/// Re-establishes the link of `inner` with exponential backoff. Returns false if `inner` was dropped meanwhile.
+///
+/// The consecutive failure count is reset when the loss is taken and again when giving up, so failures after
+/// either count towards the next loss.
+async fn reconnect(inner: &Weak<Inner>, reason: LossReason) -> bool {
+    let Some(policy) = inner.upgrade().map(|inner| {
+        warn!(%reason, "link lost, reconnecting");
+        inner.consecutive_failures.store(0, Ordering::Relaxed);
+        inner.emit(ReconnectEvent::LinkLost(reason));
+        inner.config.reconnect
+    }) else {
+        return false;
+    };
+
+    let mut attempt = 1;
+    loop {
+        if policy.max_attempts != 0 && attempt > policy.max_attempts {
+            let Some(inner) = inner.upgrade() else {
+                return false;
+            };
+            warn!(attempts = attempt - 1, "giving up reconnecting");
+            inner.consecutive_failures.store(0, Ordering::Relaxed);
+            inner.emit(ReconnectEvent::GaveUp { attempts: attempt - 1 });
+            return true;
+        }
+
+        let backoff = policy.backoff(attempt);
+        match inner.upgrade() {
+            Some(inner) => inner.emit(ReconnectEvent::Reconnecting { attempt, backoff }),
+            None => return false,
+        }
+        sleep(backoff).await;
+
+        let Some(inner) = inner.upgrade() else {
+            return false;
+        };
+        match inner.reestablish().await {
+            Ok(()) => {
+                replay(&inner).await;
+                info!(attempts = attempt, "reconnected");
+                inner.emit(ReconnectEvent::Reconnected { attempts: attempt });
+                return true;
+            }
+            Err(e) => debug!(attempt, error = %e, "reconnect attempt failed"),
+        }
+
+        attempt += 1;
+    }
+}
+
+/// Resends the last requested gimbal mode and codec settings.
+async fn replay(inner: &Inner) {
+    let frames = inner.replay.lock().unwrap().frames();
+
+    for frame in frames {
+        let result = match frame.cmd_id {
+            // Camera functions are not acknowledged
+            CMD_FUNCTION => inner.send_frame(&frame.clone().with_seq(inner.next_seq())).await,
+            _ => inner.request(frame.clone()).await.map(drop),
+        };
+
+        match result {
+            Ok(()) => debug!(cmd_id = frame.cmd_id, "replayed setting"),
+            Err(e) => warn!(cmd_id = frame.cmd_id, error = %e, "failed to replay setting"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_keeps_last_settings() {
+        let mut replay = Replay::default();
+
+        replay.record(&Frame::new(CMD_FUNCTION, vec![3]));
+        replay.record(&Frame::new(CMD_SET_CODEC, vec![1, 2]));
+        replay.record(&Frame::new(CMD_SET_CODEC, vec![0, 2]));
+        replay.record(&Frame::new(CMD_FUNCTION, vec![5]));
+        replay.record(&Frame::new(CMD_SET_CODEC, vec![1, 1]));
+
+        // Photos, recordings and other functions are not settings
+        replay.record(&Frame::new(CMD_FUNCTION, vec![0]));
+        replay.record(&Frame::new(0x0d, vec![]));
+
+        let frames: Vec<(u8, Vec<u8>)> = replay.frames().into_iter().map(|f| (f.cmd_id, f.data)).collect();
+        assert_eq!(
+            frames,
+            [(CMD_FUNCTION, vec![5]), (CMD_SET_CODEC, vec![0, 2]), (CMD_SET_CODEC, vec![1, 1])]
+        );
+    }
+}